@@ -0,0 +1,44 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{entity::obj_traits::Hittable, some_math::Point};
+
+mod aabb_impl;
+mod bvh_impl;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    pub min: Point,
+    pub max: Point,
+}
+
+// flattened bounding volume hierarchy over the scene objects, built with the
+// surface area heuristic; interior nodes keep their left child right after
+// themselves
+pub struct Bvh {
+    objects: Vec<Arc<dyn Hittable + Send + Sync>>,
+    // index into the scene lights of every object that is one, reported as
+    // `HitInfo::light`
+    lights: Vec<Option<usize>>,
+    nodes: Vec<BvhNode>,
+    stats: BvhStats,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BvhNode {
+    bbox: Aabb,
+    // leaf: index of the first object, interior: index of the right child
+    offset: usize,
+    // number of objects, 0 for interior nodes
+    count: usize,
+    // split axis of interior nodes, used to visit the near child first
+    axis: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BvhStats {
+    pub objects: usize,
+    pub nodes: usize,
+    pub leaves: usize,
+    pub depth: usize,
+    pub build_time: Duration,
+}
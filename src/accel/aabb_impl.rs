@@ -0,0 +1,115 @@
+use crate::{
+    entity::Ray,
+    some_math::{Point, Vector3},
+};
+
+use super::Aabb;
+
+impl Aabb {
+    pub const EMPTY: Aabb = Aabb {
+        min: Point {
+            data: [f64::INFINITY; 3],
+        },
+        max: Point {
+            data: [f64::NEG_INFINITY; 3],
+        },
+    };
+
+    pub fn new(min: Point, max: Point) -> Self {
+        Aabb { min, max }
+    }
+
+    pub fn from_points(points: &[Point]) -> Self {
+        let mut res = Aabb::EMPTY;
+        for point in points {
+            res = res.grow(*point);
+        }
+        return res;
+    }
+
+    pub fn grow(&self, point: Point) -> Aabb {
+        let mut res = *self;
+        for n in 0..3 {
+            res.min.data[n] = res.min.data[n].min(point.data[n]);
+            res.max.data[n] = res.max.data[n].max(point.data[n]);
+        }
+        return res;
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        let mut res = *self;
+        for n in 0..3 {
+            res.min.data[n] = res.min.data[n].min(other.min.data[n]);
+            res.max.data[n] = res.max.data[n].max(other.max.data[n]);
+        }
+        return res;
+    }
+
+    // flat boxes (panels, axis aligned triangles) get a small thickness so
+    // the slab test stays well defined
+    pub fn pad(&self, min_extent: f64) -> Aabb {
+        let mut res = *self;
+        for n in 0..3 {
+            if res.max.data[n] - res.min.data[n] < min_extent {
+                res.min.data[n] -= min_extent / 2.0;
+                res.max.data[n] += min_extent / 2.0;
+            }
+        }
+        return res;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|n| self.min.data[n] > self.max.data[n])
+    }
+
+    pub fn extent(&self) -> Vector3 {
+        self.max - self.min
+    }
+
+    pub fn centroid(&self) -> Point {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let d = self.extent();
+        return 2.0 * (d.x() * d.y() + d.y() * d.z() + d.z() * d.x());
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let d = self.extent();
+        if d.x() >= d.y() && d.x() >= d.z() {
+            return 0;
+        }
+        if d.y() >= d.z() {
+            return 1;
+        }
+        return 2;
+    }
+
+    // slab test, returns the entry distance when the box is hit before t_max
+    pub fn ray_hit(&self, ray_in: &Ray, inv_dir: &Vector3, t_max: f64) -> Option<f64> {
+        let mut t0 = 0.0;
+        let mut t1 = t_max;
+        for n in 0..3 {
+            let mut t_near = (self.min.data[n] - ray_in.origin.data[n]) * inv_dir.data[n];
+            let mut t_far = (self.max.data[n] - ray_in.origin.data[n]) * inv_dir.data[n];
+            if t_near > t_far {
+                std::mem::swap(&mut t_near, &mut t_far);
+            }
+            // comparisons written so that NaN (origin on a slab plane) is ignored
+            if t_near > t0 {
+                t0 = t_near;
+            }
+            if t_far < t1 {
+                t1 = t_far;
+            }
+            if t0 > t1 {
+                return None;
+            }
+        }
+        return Some(t0);
+    }
+}
@@ -0,0 +1,293 @@
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use crate::{
+    data::{HitInfo, HitRecord},
+    entity::{
+        obj_traits::{Hittable, HittableLight},
+        Ray,
+    },
+    some_math::{Point, Vector3},
+};
+
+use super::{Aabb, Bvh, BvhNode, BvhStats};
+
+const BIN_NUM: usize = 16;
+const MAX_LEAF_SIZE: usize = 4;
+// relative cost of visiting a node vs. intersecting an object
+const TRAVERSAL_COST: f64 = 0.5;
+
+#[derive(Clone, Copy)]
+struct BuildItem {
+    index: usize,
+    bbox: Aabb,
+    centroid: Point,
+}
+
+impl Bvh {
+    // `lights` are the emitters among `objects`, matched by pointer
+    pub fn build(
+        objects: &[Arc<dyn Hittable + Send + Sync>],
+        lights: &[Arc<dyn HittableLight + Send + Sync>],
+    ) -> Self {
+        let start = Instant::now();
+        let mut items: Vec<BuildItem> = objects
+            .iter()
+            .enumerate()
+            .map(|(index, obj)| {
+                let bbox = obj.bounding_box();
+                BuildItem {
+                    index,
+                    bbox,
+                    centroid: bbox.centroid(),
+                }
+            })
+            .collect();
+        let mut nodes = Vec::with_capacity(2 * items.len());
+        let mut stats = BvhStats {
+            objects: items.len(),
+            ..BvhStats::default()
+        };
+        if !items.is_empty() {
+            build_node(&mut items, 0, 1, &mut nodes, &mut stats);
+        }
+        stats.nodes = nodes.len();
+        let light_index: HashMap<*const (), usize> = lights
+            .iter()
+            .enumerate()
+            .map(|(index, light)| (Arc::as_ptr(light) as *const (), index))
+            .collect();
+        let objects: Vec<_> = items
+            .iter()
+            .map(|item| objects[item.index].clone())
+            .collect();
+        let lights = objects
+            .iter()
+            .map(|obj| light_index.get(&(Arc::as_ptr(obj) as *const ())).copied())
+            .collect();
+        stats.build_time = start.elapsed();
+        return Bvh {
+            objects,
+            lights,
+            nodes,
+            stats,
+        };
+    }
+
+    pub fn objects(&self) -> &[Arc<dyn Hittable + Send + Sync>] {
+        &self.objects
+    }
+
+    pub fn stats(&self) -> &BvhStats {
+        &self.stats
+    }
+
+    // box around the whole scene, empty when there are no objects
+    pub fn bounds(&self) -> Aabb {
+        match self.nodes.first() {
+            Some(root) => root.bbox,
+            None => Aabb::EMPTY,
+        }
+    }
+
+    pub fn ray_hit(&self, ray_in: &Ray, dismiss_light: bool) -> Option<HitInfo> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let inv_dir = inverse_direction(&ray_in.direction);
+        // nearest (object, record) so far, surface data is built once at the end
+        let mut closest: Option<(usize, HitRecord)> = None;
+        let mut t = f64::INFINITY;
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0);
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.bbox.ray_hit(ray_in, &inv_dir, t).is_none() {
+                continue;
+            }
+            if node.count > 0 {
+                for obj_index in node.offset..node.offset + node.count {
+                    let obj = &self.objects[obj_index];
+                    if dismiss_light && obj.is_light() {
+                        continue;
+                    }
+                    if let Some(record) = obj.intersect(ray_in) {
+                        if record.t < t {
+                            t = record.t;
+                            closest = Some((obj_index, record));
+                        }
+                    }
+                }
+            } else if ray_in.direction.data[node.axis] < 0.0 {
+                // right child is nearer, visit it first
+                stack.push(index + 1);
+                stack.push(node.offset);
+            } else {
+                stack.push(node.offset);
+                stack.push(index + 1);
+            }
+        }
+        return closest.map(|(index, record)| {
+            let mut info = self.objects[index].surface_info(ray_in, &record);
+            info.light = self.lights[index];
+            info
+        });
+    }
+
+    // any-hit query for shadow rays, stops at the first blocker closer than t_max
+    pub fn occluded(&self, ray_in: &Ray, t_max: f64, dismiss_light: bool) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let inv_dir = inverse_direction(&ray_in.direction);
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0);
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.bbox.ray_hit(ray_in, &inv_dir, t_max).is_none() {
+                continue;
+            }
+            if node.count > 0 {
+                for obj in self.objects[node.offset..node.offset + node.count].iter() {
+                    if dismiss_light && obj.is_light() {
+                        continue;
+                    }
+                    if obj.occluded(ray_in, t_max) {
+                        return true;
+                    }
+                }
+            } else {
+                stack.push(node.offset);
+                stack.push(index + 1);
+            }
+        }
+        return false;
+    }
+}
+
+fn inverse_direction(direction: &Vector3) -> Vector3 {
+    Vector3::new([
+        1.0 / direction.data[0],
+        1.0 / direction.data[1],
+        1.0 / direction.data[2],
+    ])
+}
+
+fn build_node(
+    items: &mut [BuildItem],
+    first: usize,
+    depth: usize,
+    nodes: &mut Vec<BvhNode>,
+    stats: &mut BvhStats,
+) -> usize {
+    let node_index = nodes.len();
+    let bbox = items
+        .iter()
+        .fold(Aabb::EMPTY, |acc, item| acc.union(&item.bbox));
+    nodes.push(BvhNode {
+        bbox,
+        offset: first,
+        count: items.len(),
+        axis: 0,
+    });
+    stats.depth = stats.depth.max(depth);
+
+    let split = if items.len() > 1 {
+        find_split(items, &bbox)
+    } else {
+        None
+    };
+    let (axis, mid) = match split {
+        Some(split) => split,
+        None => {
+            stats.leaves += 1;
+            return node_index;
+        }
+    };
+
+    build_node(&mut items[..mid], first, depth + 1, nodes, stats);
+    let right = build_node(&mut items[mid..], first + mid, depth + 1, nodes, stats);
+    nodes[node_index] = BvhNode {
+        bbox,
+        offset: right,
+        count: 0,
+        axis,
+    };
+    return node_index;
+}
+
+// binned SAH, partitions `items` in place and returns (axis, split position),
+// or None when a leaf is cheaper
+fn find_split(items: &mut [BuildItem], bbox: &Aabb) -> Option<(usize, usize)> {
+    let centroid_bounds = items
+        .iter()
+        .fold(Aabb::EMPTY, |acc, item| acc.grow(item.centroid));
+    let axis = centroid_bounds.longest_axis();
+    let c_min = centroid_bounds.min.data[axis];
+    let c_extent = centroid_bounds.max.data[axis] - c_min;
+    if c_extent <= 0.0 {
+        // all centroids coincide, nothing to split on
+        return None;
+    }
+    let bin_of = |item: &BuildItem| {
+        let bin = ((item.centroid.data[axis] - c_min) / c_extent * BIN_NUM as f64) as usize;
+        bin.min(BIN_NUM - 1)
+    };
+
+    let mut bin_counts = [0usize; BIN_NUM];
+    let mut bin_boxes = [Aabb::EMPTY; BIN_NUM];
+    for item in items.iter() {
+        let bin = bin_of(item);
+        bin_counts[bin] += 1;
+        bin_boxes[bin] = bin_boxes[bin].union(&item.bbox);
+    }
+
+    // sweep from the right to get the area / count of every right side
+    let mut right_area = [0.0; BIN_NUM];
+    let mut right_count = [0usize; BIN_NUM];
+    let mut acc_box = Aabb::EMPTY;
+    let mut acc_count = 0;
+    for bin in (1..BIN_NUM).rev() {
+        acc_box = acc_box.union(&bin_boxes[bin]);
+        acc_count += bin_counts[bin];
+        right_area[bin] = acc_box.surface_area();
+        right_count[bin] = acc_count;
+    }
+
+    let mut best_cost = f64::INFINITY;
+    let mut best_bin = 0;
+    let mut acc_box = Aabb::EMPTY;
+    let mut acc_count = 0;
+    for bin in 0..BIN_NUM - 1 {
+        acc_box = acc_box.union(&bin_boxes[bin]);
+        acc_count += bin_counts[bin];
+        if acc_count == 0 || right_count[bin + 1] == 0 {
+            continue;
+        }
+        let cost = acc_box.surface_area() * acc_count as f64
+            + right_area[bin + 1] * right_count[bin + 1] as f64;
+        if cost < best_cost {
+            best_cost = cost;
+            best_bin = bin;
+        }
+    }
+    let area = bbox.surface_area();
+    let split_cost = if area > 0.0 {
+        TRAVERSAL_COST + best_cost / area
+    } else {
+        TRAVERSAL_COST
+    };
+    if best_cost == f64::INFINITY
+        || (items.len() <= MAX_LEAF_SIZE && split_cost >= items.len() as f64)
+    {
+        return None;
+    }
+
+    let mut mid = 0;
+    for n in 0..items.len() {
+        if bin_of(&items[n]) <= best_bin {
+            items.swap(n, mid);
+            mid += 1;
+        }
+    }
+    return Some((axis, mid));
+}
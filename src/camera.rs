@@ -1,8 +1,7 @@
-use rand::{prelude::ThreadRng, Rng};
-
 use crate::{
     entity::Ray,
-    settings::{ASPECT_RATIO, CAMERA_HEIGHT, FOV, SAMPLES_PER_PIXEL, WINDOW_HEIGHT, WINDOW_WIDTH},
+    sampler::Sampler,
+    settings::{RenderSettings, CAMERA_HEIGHT},
     some_math::{Point, Vector3},
 };
 
@@ -19,15 +18,22 @@ pub struct Camera {
 }
 
 impl Camera {
-    pub fn new(position: Point, lookat: Vector3, updir: Vector3) -> Self {
+    pub fn new(
+        position: Point,
+        lookat: Vector3,
+        updir: Vector3,
+        settings: &RenderSettings,
+    ) -> Self {
         let u = lookat.cross_product(updir);
         let v = updir;
         let view_height = CAMERA_HEIGHT;
-        let view_width = CAMERA_HEIGHT * ASPECT_RATIO;
+        let view_width = CAMERA_HEIGHT * settings.aspect_ratio();
+        let window_height = settings.height as f64;
+        let window_width = settings.width as f64;
         // let view_length = (view_height / 2.0) / (FOV / 2.0).to_radians().tan();
-        let window_length = (WINDOW_HEIGHT as f64 / 2.0) / (FOV / 2.0).to_radians().tan();
-        let upper_left_point = position + window_length * lookat + (WINDOW_HEIGHT as f64 / 2.0) * v
-            - (WINDOW_WIDTH as f64 / 2.0) * u;
+        let window_length = (window_height / 2.0) / (settings.fov / 2.0).to_radians().tan();
+        let upper_left_point = position + window_length * lookat + (window_height / 2.0) * v
+            - (window_width / 2.0) * u;
         Camera {
             position,
             lookat,
@@ -40,26 +46,39 @@ impl Camera {
         }
     }
 
-    pub fn generate_rays(
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    pub fn lookat(&self) -> Vector3 {
+        self.lookat
+    }
+
+    pub fn updir(&self) -> Vector3 {
+        self.updir
+    }
+
+    pub fn view_size(&self) -> (f64, f64) {
+        (self.view_width, self.view_height)
+    }
+
+    // the first sample goes through the pixel center, the others are jittered
+    pub fn generate_ray(
         &self,
         col_num: u32,
         row_num: u32,
-        rng: &mut ThreadRng,
-    ) -> [Ray; SAMPLES_PER_PIXEL] {
-        let mut res = [Ray::default(); SAMPLES_PER_PIXEL];
-        for n in 0..SAMPLES_PER_PIXEL {
-            let target;
-            if n == 0 {
-                target = self.upper_left_point + (col_num as f64 + 0.5) * self.u
-                    - (row_num as f64 + 0.5) * self.v;
-            } else {
-                target = self.upper_left_point
-                    + (col_num as f64 + rng.gen_range(0.0..1.0)) * self.u
-                    - (row_num as f64 + rng.gen_range(0.0..1.0)) * self.v;
-            }
-            let ray = Ray::new(self.position, (target - self.position).normalize());
-            res[n] = ray;
-        }
-        return res;
+        sample: usize,
+        sampler: &mut dyn Sampler,
+    ) -> Ray {
+        // drawn for the center sample as well, keeps the later dimensions aligned
+        let jitter = sampler.get_2d();
+        let target = if sample == 0 {
+            self.upper_left_point + (col_num as f64 + 0.5) * self.u
+                - (row_num as f64 + 0.5) * self.v
+        } else {
+            self.upper_left_point + (col_num as f64 + jitter[0]) * self.u
+                - (row_num as f64 + jitter[1]) * self.v
+        };
+        return Ray::new(self.position, (target - self.position).normalize());
     }
 }
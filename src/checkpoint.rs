@@ -0,0 +1,54 @@
+use std::{fmt::Display, io};
+
+use crate::{sampler::SamplerKind, tile::Tile};
+
+mod io_impl;
+
+pub use io_impl::{merge_checkpoints, read_checkpoint, write_checkpoint};
+
+// what a render needs besides the frame buffer to continue where it stopped.
+// the same file serves as partial render, several of them are merged into one
+// image.
+// samplers are seeded per pixel and sample, so the seed together with the
+// sample counts of the frame is the whole random state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckpointInfo {
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub sampler: SamplerKind,
+    // spp the samplers were built for, stratification depends on it
+    pub samples_per_pixel: usize,
+    // cheap check that the checkpoint belongs to the loaded scene
+    pub object_count: usize,
+    // part of the image that was rendered, the other pixels hold no samples
+    pub region: Tile,
+}
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    Format(String),
+    // the checkpoint was written for other settings or another scene
+    Mismatch(String),
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Io(err) => write!(f, "cannot read checkpoint: {}", err),
+            CheckpointError::Format(message) => write!(f, "invalid checkpoint: {}", message),
+            CheckpointError::Mismatch(message) => {
+                write!(f, "checkpoint does not match the render: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(err: io::Error) -> Self {
+        CheckpointError::Io(err)
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{data::FrameBuffer, sampler::SamplerKind, tile::Tile};
+
+use super::{CheckpointError, CheckpointInfo};
+
+const MAGIC: &[u8; 8] = b"RTDCKPT\0";
+const VERSION: u32 = 2;
+const HEADER_VALUES: usize = 9;
+
+// little endian header followed by the raw frame buffer. written next to the
+// target and renamed over it, so a crash never leaves a half written file
+pub fn write_checkpoint<P: AsRef<Path>>(
+    path: P,
+    info: &CheckpointInfo,
+    frame: &FrameBuffer,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(".tmp");
+    let temp_path = Path::new(&temp_name);
+    {
+        let mut out = BufWriter::new(File::create(temp_path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        for value in [
+            info.width as u64,
+            info.height as u64,
+            info.seed,
+            info.samples_per_pixel as u64,
+            info.object_count as u64,
+            info.region.x as u64,
+            info.region.y as u64,
+            info.region.width as u64,
+            info.region.height as u64,
+        ] {
+            out.write_all(&value.to_le_bytes())?;
+        }
+        let label = info.sampler.label().as_bytes();
+        out.write_all(&[label.len() as u8])?;
+        out.write_all(label)?;
+        frame.write_raw(&mut out)?;
+        out.into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()?;
+    }
+    return fs::rename(temp_path, path);
+}
+
+pub fn read_checkpoint<P: AsRef<Path>>(
+    path: P,
+) -> Result<(CheckpointInfo, FrameBuffer), CheckpointError> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut input = BufReader::new(file);
+    let mut magic = [0; 8];
+    input.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(CheckpointError::Format("not a checkpoint file".to_string()));
+    }
+    let mut version = [0; 4];
+    input.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != VERSION {
+        return Err(CheckpointError::Format(format!(
+            "unsupported version {}",
+            version
+        )));
+    }
+    let mut values = [0; HEADER_VALUES];
+    for value in values.iter_mut() {
+        let mut bytes = [0; 8];
+        input.read_exact(&mut bytes)?;
+        *value = u64::from_le_bytes(bytes);
+    }
+    let mut label_len = [0; 1];
+    input.read_exact(&mut label_len)?;
+    let mut label = vec![0; label_len[0] as usize];
+    input.read_exact(&mut label)?;
+    let label = String::from_utf8_lossy(&label);
+    let sampler = SamplerKind::from_label(&label)
+        .ok_or_else(|| CheckpointError::Format(format!("unknown sampler {}", label)))?;
+    // the region is combined with others in u32 arithmetic, it has to lie
+    // inside the image
+    let [width, height, _, _, _, x, y, region_width, region_height] = values;
+    if x.checked_add(region_width).is_none_or(|end| end > width)
+        || y.checked_add(region_height).is_none_or(|end| end > height)
+        || width > u32::MAX as u64
+        || height > u32::MAX as u64
+    {
+        return Err(CheckpointError::Format(format!(
+            "region {}x{} at ({}, {}) does not fit into a {}x{} frame",
+            region_width, region_height, x, y, width, height
+        )));
+    }
+    let info = CheckpointInfo {
+        width: values[0] as usize,
+        height: values[1] as usize,
+        seed: values[2],
+        sampler,
+        samples_per_pixel: values[3] as usize,
+        object_count: values[4] as usize,
+        region: Tile::new(
+            values[5] as u32,
+            values[6] as u32,
+            values[7] as u32,
+            values[8] as u32,
+        ),
+    };
+    // checked before allocating, a broken header could ask for any size
+    let header_len = (MAGIC.len() + 4 + HEADER_VALUES * 8 + 1 + label_len[0] as usize) as u64;
+    if FrameBuffer::raw_size(info.width, info.height) != file_len.checked_sub(header_len) {
+        return Err(CheckpointError::Format(format!(
+            "file size does not match a {}x{} frame",
+            info.width, info.height
+        )));
+    }
+    let frame = FrameBuffer::read_raw(info.width, info.height, &mut input)?;
+    return Ok((info, frame));
+}
+
+// combines partial renders of one image, split by region or by seed. renders
+// with the same seed take the same samples, so their regions must not overlap
+pub fn merge_checkpoints<P: AsRef<Path>>(
+    paths: &[P],
+) -> Result<(CheckpointInfo, FrameBuffer), CheckpointError> {
+    let mut merged: Option<(CheckpointInfo, FrameBuffer)> = None;
+    let mut parts: Vec<CheckpointInfo> = Vec::with_capacity(paths.len());
+    for path in paths.iter() {
+        let (info, frame) = read_checkpoint(path)?;
+        for part in parts.iter() {
+            if (part.width, part.height) != (info.width, info.height) {
+                return Err(CheckpointError::Mismatch(format!(
+                    "{} is {}x{}, the others are {}x{}",
+                    path.as_ref().display(),
+                    info.width,
+                    info.height,
+                    part.width,
+                    part.height
+                )));
+            }
+            if part.seed == info.seed && part.region.intersect(&info.region).pixel_count() > 0 {
+                return Err(CheckpointError::Mismatch(format!(
+                    "{} overlaps another render with seed {}, the samples would count twice",
+                    path.as_ref().display(),
+                    info.seed
+                )));
+            }
+        }
+        parts.push(info);
+        match &mut merged {
+            Some((merged_info, merged_frame)) => {
+                merged_frame.merge(&frame);
+                merged_info.region = bounding_region(&merged_info.region, &info.region);
+            }
+            None => merged = Some((info, frame)),
+        }
+    }
+    return merged.ok_or_else(|| CheckpointError::Format("nothing to merge".to_string()));
+}
+
+fn bounding_region(a: &Tile, b: &Tile) -> Tile {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    return Tile::new(
+        x,
+        y,
+        (a.x + a.width).max(b.x + b.width) - x,
+        (a.y + a.height).max(b.y + b.height) - y,
+    );
+}
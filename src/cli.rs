@@ -0,0 +1,189 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, ValueEnum};
+
+mod args_impl;
+
+use args_impl::{parse_fov, parse_rows, parse_threshold, parse_time_limit};
+
+/// Path tracer with a G-buffer guided denoising pipeline.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Args {
+    /// Scene description file (TOML), the built-in Cornell box is used when omitted
+    pub scene: Option<PathBuf>,
+
+    /// Path of the final image
+    #[arg(short, long, default_value = "output.png")]
+    pub output: PathBuf,
+
+    /// Image width in pixels [default: 600]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: Option<u32>,
+
+    /// Image height in pixels [default: 600]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: Option<u32>,
+
+    /// Vertical field of view in degrees [default: 40]
+    #[arg(long, value_parser = parse_fov)]
+    pub fov: Option<f64>,
+
+    /// Samples per pixel [default: 1]
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub spp: Option<u32>,
+
+    /// Render progressively in passes adding this many samples per pixel
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub pass_spp: Option<u32>,
+
+    /// Seconds between writes of the current estimate to <OUTPUT>-progress.png
+    /// while rendering in passes [default: 0, after every pass]
+    #[arg(long, requires = "pass_spp")]
+    pub progress_interval: Option<u64>,
+
+    /// Keep sampling only pixels whose relative error is above this, up to
+    /// --spp samples
+    #[arg(long, value_parser = parse_threshold)]
+    pub adaptive: Option<f64>,
+
+    /// Samples every pixel takes before adaptive sampling may stop it [default: 8]
+    #[arg(long, requires = "adaptive", value_parser = clap::value_parser!(u32).range(2..))]
+    pub adaptive_min_spp: Option<u32>,
+
+    /// Also write the g-buffer normals and distances to <OUTPUT>-normal.png
+    /// and <OUTPUT>-depth.png
+    #[arg(long)]
+    pub save_gbuffer: bool,
+
+    /// Save the accumulated samples to this file between passes and at the end,
+    /// renders in passes of 16 samples unless --pass-spp or --adaptive is given
+    #[arg(long)]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Seconds between two checkpoints [default: 60]
+    #[arg(long, requires = "checkpoint")]
+    pub checkpoint_interval: Option<u64>,
+
+    /// Continue the render saved in this checkpoint, the scene and settings
+    /// have to match
+    #[arg(long)]
+    pub resume: Option<PathBuf>,
+
+    /// Merge these partial renders (checkpoint files) instead of rendering,
+    /// then denoise and write the result
+    #[arg(long, num_args = 1.., conflicts_with_all = ["scene", "resume"])]
+    pub merge: Vec<PathBuf>,
+
+    /// Hand the tiles to worker processes connecting to this address
+    /// (e.g. 127.0.0.1:7878) instead of rendering them here, -j tiles are
+    /// out at once
+    #[arg(long, conflicts_with = "merge")]
+    pub serve: Option<String>,
+
+    /// Run as worker for the coordinator at this address, the scene has to be
+    /// the one the coordinator renders
+    #[arg(long, conflicts_with_all = ["serve", "merge", "resume"])]
+    pub worker: Option<String>,
+
+    /// Also write the samples taken per pixel to <OUTPUT>-spp.png
+    #[arg(long)]
+    pub save_heatmap: bool,
+
+    /// Stop rendering after this many seconds and keep what is done
+    #[arg(long, value_parser = parse_time_limit)]
+    pub time_limit: Option<Duration>,
+
+    /// Stop rendering after this many samples over the whole image
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub sample_budget: Option<u64>,
+
+    /// Only render the rows START..END, the partial render is saved with
+    /// --checkpoint and merged later
+    #[arg(long, value_parser = parse_rows)]
+    pub rows: Option<(u32, u32)>,
+
+    /// Maximum number of bounces per path [default: 20]
+    #[arg(short = 'd', long, value_parser = clap::value_parser!(i32).range(0..))]
+    pub max_depth: Option<i32>,
+
+    /// Bounces before Russian roulette may end a path, at or above
+    /// --max-depth turns it off [default: 3]
+    #[arg(long, value_parser = clap::value_parser!(i32).range(0..))]
+    pub roulette_depth: Option<i32>,
+
+    /// Number of render threads [default: 4]
+    #[arg(short = 'j', long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub threads: Option<u32>,
+
+    /// Number of filter steps used by the row / col filters [default: 4]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub filter_step: Option<u32>,
+
+    /// Seed of the random sequences, the same seed gives the same image
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Sample sequence used for pixel, light and material samples [default: sobol]
+    #[arg(long, value_enum)]
+    pub sampler: Option<SamplerArg>,
+
+    /// How camera rays are shaded, the path tracer or a faster or debug view [default: path]
+    #[arg(long, value_enum)]
+    pub integrator: Option<IntegratorArg>,
+
+    /// Edge length of the render tiles in pixels [default: 32]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub tile_size: Option<u32>,
+
+    /// Order in which tiles are rendered [default: hilbert]
+    #[arg(long, value_enum)]
+    pub tile_order: Option<TileOrderArg>,
+
+    /// Denoise stages to run in order [default: outlier,row,outlier,col,outlier]
+    #[arg(long, value_enum, value_delimiter = ',', conflicts_with = "no_denoise")]
+    pub denoise: Option<Vec<StageArg>>,
+
+    /// Skip the denoising pipeline and write the raw render
+    #[arg(long)]
+    pub no_denoise: bool,
+
+    /// Also write the image produced by every stage next to the output
+    #[arg(long)]
+    pub save_intermediate: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SamplerArg {
+    Random,
+    Stratified,
+    Halton,
+    Sobol,
+    BlueNoise,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum IntegratorArg {
+    Path,
+    Direct,
+    Ao,
+    Whitted,
+    Normals,
+    Depth,
+    ObjectId,
+    Albedo,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum TileOrderArg {
+    Scanline,
+    Spiral,
+    Hilbert,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum StageArg {
+    Outlier,
+    Row,
+    Col,
+}
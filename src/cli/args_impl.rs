@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use clap::{error::ErrorKind, CommandFactory};
+
+use ray_tracing_demo::{
+    AdaptiveSettings, CheckpointSettings, DenoiseStage, IntegratorKind, OutputSettings,
+    RenderBudget, RenderSettings, SamplerKind, Tile, TileOrder,
+};
+
+use super::{Args, IntegratorArg, SamplerArg, StageArg, TileOrderArg};
+
+// checkpoints are written between passes, so a checkpointed render is split
+// into passes of this many samples unless told otherwise
+const CHECKPOINT_PASS_SPP: usize = 16;
+
+impl Args {
+    // checks across arguments that clap can not express, reported like its own errors
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        let height = self.height.unwrap_or(RenderSettings::default().height);
+        if let Some((start, end)) = self.rows {
+            if end > height {
+                return Err(Args::command().error(
+                    ErrorKind::ValueValidation,
+                    format!(
+                        "--rows {}..{} does not fit into the {} rows of the image",
+                        start, end, height
+                    ),
+                ));
+            }
+        }
+        return Ok(());
+    }
+
+    pub fn render_settings(&self) -> RenderSettings {
+        let default = RenderSettings::default();
+        RenderSettings {
+            width: self.width.unwrap_or(default.width),
+            height: self.height.unwrap_or(default.height),
+            fov: self.fov.unwrap_or(default.fov),
+            samples_per_pixel: self
+                .spp
+                .map_or(default.samples_per_pixel, |spp| spp as usize),
+            pass_spp: self.pass_spp.map(|spp| spp as usize).or_else(|| {
+                // adaptive sampling renders in passes already
+                if self.checkpoint.is_some() && self.adaptive.is_none() {
+                    Some(CHECKPOINT_PASS_SPP)
+                } else {
+                    None
+                }
+            }),
+            adaptive: self.adaptive.map(|threshold| {
+                let default = AdaptiveSettings::default();
+                AdaptiveSettings {
+                    threshold,
+                    min_spp: self
+                        .adaptive_min_spp
+                        .map_or(default.min_spp, |spp| spp as usize),
+                }
+            }),
+            budget: RenderBudget {
+                time: self.time_limit,
+                samples: self.sample_budget.map(|samples| samples as usize),
+            },
+            region: self.rows.map(|(start, end)| {
+                Tile::new(0, start, self.width.unwrap_or(default.width), end - start)
+            }),
+            ray_depth: self.max_depth.unwrap_or(default.ray_depth),
+            roulette_depth: self.roulette_depth.unwrap_or(default.roulette_depth),
+            thread_num: self
+                .threads
+                .map_or(default.thread_num, |threads| threads as usize),
+            filter_step: self
+                .filter_step
+                .map_or(default.filter_step, |step| step as usize),
+            seed: self.seed,
+            sampler: self
+                .sampler
+                .map_or(default.sampler, |sampler| sampler.to_kind()),
+            integrator: self
+                .integrator
+                .map_or(default.integrator, |integrator| integrator.to_kind()),
+            tile_size: self.tile_size.unwrap_or(default.tile_size),
+            tile_order: self
+                .tile_order
+                .map_or(default.tile_order, |order| order.to_order()),
+        }
+    }
+
+    pub fn output_settings(&self) -> OutputSettings {
+        let denoise_stages = if self.no_denoise {
+            Vec::new()
+        } else if let Some(stages) = &self.denoise {
+            stages.iter().map(|stage| stage.to_stage()).collect()
+        } else {
+            DenoiseStage::DEFAULT_PIPELINE.to_vec()
+        };
+        OutputSettings {
+            path: self.output.clone(),
+            save_intermediate: self.save_intermediate,
+            denoise_stages,
+            progress_interval: self
+                .pass_spp
+                .map(|_| Duration::from_secs(self.progress_interval.unwrap_or(0))),
+            save_heatmap: self.save_heatmap,
+            save_gbuffer: self.save_gbuffer,
+            checkpoint: self.checkpoint.as_ref().map(|path| CheckpointSettings {
+                path: path.clone(),
+                interval: Duration::from_secs(self.checkpoint_interval.unwrap_or(60)),
+            }),
+        }
+    }
+}
+
+impl SamplerArg {
+    fn to_kind(self) -> SamplerKind {
+        match self {
+            SamplerArg::Random => SamplerKind::Random,
+            SamplerArg::Stratified => SamplerKind::Stratified,
+            SamplerArg::Halton => SamplerKind::Halton,
+            SamplerArg::Sobol => SamplerKind::Sobol,
+            SamplerArg::BlueNoise => SamplerKind::BlueNoise,
+        }
+    }
+}
+
+impl IntegratorArg {
+    fn to_kind(self) -> IntegratorKind {
+        match self {
+            IntegratorArg::Path => IntegratorKind::Path,
+            IntegratorArg::Direct => IntegratorKind::Direct,
+            IntegratorArg::Ao => IntegratorKind::AmbientOcclusion,
+            IntegratorArg::Whitted => IntegratorKind::Whitted,
+            IntegratorArg::Normals => IntegratorKind::Normals,
+            IntegratorArg::Depth => IntegratorKind::Depth,
+            IntegratorArg::ObjectId => IntegratorKind::ObjectId,
+            IntegratorArg::Albedo => IntegratorKind::Albedo,
+        }
+    }
+}
+
+impl TileOrderArg {
+    fn to_order(self) -> TileOrder {
+        match self {
+            TileOrderArg::Scanline => TileOrder::Scanline,
+            TileOrderArg::Spiral => TileOrder::Spiral,
+            TileOrderArg::Hilbert => TileOrder::Hilbert,
+        }
+    }
+}
+
+impl StageArg {
+    fn to_stage(self) -> DenoiseStage {
+        match self {
+            StageArg::Outlier => DenoiseStage::OutlierRemoval,
+            StageArg::Row => DenoiseStage::RowFilter,
+            StageArg::Col => DenoiseStage::ColFilter,
+        }
+    }
+}
+
+pub fn parse_fov(value: &str) -> Result<f64, String> {
+    let fov: f64 = value.parse().map_err(|err| format!("{}", err))?;
+    if fov > 0.0 && fov < 180.0 {
+        return Ok(fov);
+    }
+    return Err(format!("{} is not in (0, 180)", fov));
+}
+
+pub fn parse_threshold(value: &str) -> Result<f64, String> {
+    let threshold: f64 = value.parse().map_err(|err| format!("{}", err))?;
+    if threshold > 0.0 {
+        return Ok(threshold);
+    }
+    return Err(format!("{} is not positive", threshold));
+}
+
+pub fn parse_time_limit(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value.parse().map_err(|err| format!("{}", err))?;
+    if seconds > 0.0 && seconds.is_finite() {
+        return Duration::try_from_secs_f64(seconds).map_err(|err| err.to_string());
+    }
+    return Err(format!("{} is not a positive number of seconds", seconds));
+}
+
+pub fn parse_rows(value: &str) -> Result<(u32, u32), String> {
+    let (start, end) = value
+        .split_once("..")
+        .ok_or_else(|| format!("{} is not START..END", value))?;
+    let start: u32 = start.parse().map_err(|err| format!("{}", err))?;
+    let end: u32 = end.parse().map_err(|err| format!("{}", err))?;
+    if start < end {
+        return Ok((start, end));
+    }
+    return Err(format!("{}..{} is empty", start, end));
+}
@@ -1,14 +1,36 @@
-use std::sync::Arc;
+use std::sync::{atomic::AtomicU64, Arc};
 
 use crate::{
-    material::Material,
+    material::{BsdfFlags, Material},
     some_math::{Color, Point, Vector3},
 };
 
+mod frame_buffer_impl;
 mod gbuffer_impl;
 mod pixel_data_impl;
 
+// f64 values of GBInfo stored per pixel: distance, normal, hit point, object id
+const GBUFFER_STRIDE: usize = 8;
+// everything a pixel of the frame buffer holds: radiance sum, luminance
+// square sum, sample count and g-buffer
+pub const PIXEL_WORDS: usize = 3 + 1 + 1 + GBUFFER_STRIDE;
+
+// image and g-buffer shared by all render threads. every thread writes its own
+// tiles, stored as f64 bits in relaxed atomics so no lock is taken per pixel
+pub struct FrameBuffer {
+    width: usize,
+    height: usize,
+    // running radiance sums, divided by the sample count on read
+    radiance: Vec<AtomicU64>,
+    // sums of squared luminance, for the variance used by adaptive sampling
+    squares: Vec<AtomicU64>,
+    counts: Vec<AtomicU64>,
+    gbuffer: Vec<AtomicU64>,
+}
+
 pub struct PixelContainer {
+    width: usize,
+    height: usize,
     data: Vec<RowColPixels>,
 }
 
@@ -19,6 +41,7 @@ pub struct RowColPixels {
 
 #[derive(Debug, Clone)]
 pub struct GeometryBuffer {
+    height: usize,
     data: Vec<RowColGBuffer>,
 }
 
@@ -42,13 +65,33 @@ pub struct HitInfo {
     pub normal: Vector3,
     pub material: Arc<dyn Material>,
     pub obj_id: usize,
+    // index into the scene lights when the hit object is one, set by the bvh
+    pub light: Option<usize>,
+    // weights of the three vertices, only meaningful for triangles
+    pub barycentric: [f64; 3],
+    // surface parameterization, interpolated texcoords for triangles
+    pub uv: [f64; 2],
+}
+
+// result of the cheap intersection pass, turned into a HitInfo only for the closest hit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HitRecord {
+    pub t: f64,
+    // primitive specific: barycentrics for triangles, box frame normal for rectangles
+    pub local: [f64; 3],
 }
 
+// a direction drawn from a bsdf
 #[derive(Debug, Clone, Copy)]
-pub struct ScatterInfo {
-    pub scatter_dir: Vector3,
-    pub color: Color,
+pub struct BsdfSample {
+    // incident direction, pointing away from the surface
+    pub wi: Vector3,
+    // f * |cos| / pdf, what the path throughput gets multiplied by
+    pub weight: Color,
+    // solid angle density, the discrete probability of the lobe for delta lobes
     pub pdf: f64,
+    // the lobe the direction was drawn from
+    pub flags: BsdfFlags,
 }
 
 #[derive(Debug, Clone, Copy)]
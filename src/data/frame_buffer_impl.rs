@@ -0,0 +1,344 @@
+use std::{
+    io::{self, Read, Write},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{settings::AdaptiveSettings, some_math::Vector3, tile::Tile};
+
+use super::{
+    FrameBuffer, GBInfo, GeometryBuffer, PixelContainer, RowColGBuffer, RowColPixels,
+    GBUFFER_STRIDE, PIXEL_WORDS,
+};
+
+impl FrameBuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        FrameBuffer {
+            width,
+            height,
+            radiance: (0..width * height * 3).map(|_| AtomicU64::new(0)).collect(),
+            squares: (0..width * height).map(|_| AtomicU64::new(0)).collect(),
+            counts: (0..width * height).map(|_| AtomicU64::new(0)).collect(),
+            gbuffer: (0..width * height * GBUFFER_STRIDE)
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn sample_count(&self, col_num: usize, row_num: usize) -> usize {
+        self.counts[row_num * self.width + col_num].load(Ordering::Relaxed) as usize
+    }
+
+    // fewest samples held by any pixel of `region`, pixels outside it are
+    // never rendered
+    pub fn min_sample_count(&self, region: &Tile) -> usize {
+        return region
+            .pixels()
+            .map(|(col_num, row_num)| self.sample_count(col_num as usize, row_num as usize))
+            .min()
+            .unwrap_or(0);
+    }
+
+    pub fn max_sample_count(&self) -> usize {
+        return self
+            .counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed) as usize)
+            .max()
+            .unwrap_or(0);
+    }
+
+    pub fn total_sample_count(&self) -> usize {
+        return self
+            .counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed) as usize)
+            .sum();
+    }
+
+    pub fn radiance_sum(&self, col_num: usize, row_num: usize) -> [f64; 3] {
+        let base = (row_num * self.width + col_num) * 3;
+        let mut res = [0.0; 3];
+        for (n, value) in res.iter_mut().enumerate() {
+            *value = f64::from_bits(self.radiance[base + n].load(Ordering::Relaxed));
+        }
+        return res;
+    }
+
+    pub fn luminance_square_sum(&self, col_num: usize, row_num: usize) -> f64 {
+        let index = row_num * self.width + col_num;
+        return f64::from_bits(self.squares[index].load(Ordering::Relaxed));
+    }
+
+    // a pixel is only written by the thread rendering its tile, so a plain
+    // store after reading the old sums is enough
+    pub fn set_radiance_sum(
+        &self,
+        col_num: usize,
+        row_num: usize,
+        sum: [f64; 3],
+        square_sum: f64,
+        count: usize,
+    ) {
+        let index = row_num * self.width + col_num;
+        for (n, value) in sum.iter().enumerate() {
+            self.radiance[index * 3 + n].store(value.to_bits(), Ordering::Relaxed);
+        }
+        self.squares[index].store(square_sum.to_bits(), Ordering::Relaxed);
+        self.counts[index].store(count as u64, Ordering::Relaxed);
+    }
+
+    // standard error of the mean luminance relative to the mean, infinite
+    // until there are two samples to estimate the variance from
+    pub fn relative_error(&self, col_num: usize, row_num: usize) -> f64 {
+        let count = self.sample_count(col_num, row_num);
+        if count < 2 {
+            return f64::INFINITY;
+        }
+        let n = count as f64;
+        let mean = Vector3::new(self.radiance_sum(col_num, row_num)).luminance() / n;
+        let square_mean = self.luminance_square_sum(col_num, row_num) / n;
+        let variance = ((square_mean - mean * mean) * n / (n - 1.0)).max(0.0);
+        // the small offset keeps black pixels from never converging
+        return (variance / n).sqrt() / (mean + 1e-3);
+    }
+
+    // whether a pixel should take more samples, always true below the
+    // adaptive minimum
+    pub fn needs_samples(
+        &self,
+        col_num: usize,
+        row_num: usize,
+        max_spp: usize,
+        adaptive: Option<&AdaptiveSettings>,
+    ) -> bool {
+        let count = self.sample_count(col_num, row_num);
+        if count >= max_spp {
+            return false;
+        }
+        return match adaptive {
+            Some(adaptive) if count >= adaptive.min_spp => {
+                self.relative_error(col_num, row_num) > adaptive.threshold
+            }
+            _ => true,
+        };
+    }
+
+    pub fn pending_pixels(
+        &self,
+        region: &Tile,
+        max_spp: usize,
+        adaptive: Option<&AdaptiveSettings>,
+    ) -> usize {
+        return region
+            .pixels()
+            .filter(|&(col_num, row_num)| {
+                self.needs_samples(col_num as usize, row_num as usize, max_spp, adaptive)
+            })
+            .count();
+    }
+
+    // whole state of one pixel, lets it move to another process and back
+    pub fn pixel_words(&self, col_num: usize, row_num: usize) -> [u64; PIXEL_WORDS] {
+        let index = row_num * self.width + col_num;
+        let radiance = &self.radiance[index * 3..index * 3 + 3];
+        let gbuffer = &self.gbuffer[index * GBUFFER_STRIDE..(index + 1) * GBUFFER_STRIDE];
+        let mut words = [0; PIXEL_WORDS];
+        for (word, value) in words[..3].iter_mut().zip(radiance) {
+            *word = value.load(Ordering::Relaxed);
+        }
+        words[3] = self.squares[index].load(Ordering::Relaxed);
+        words[4] = self.counts[index].load(Ordering::Relaxed);
+        for (word, value) in words[5..].iter_mut().zip(gbuffer) {
+            *word = value.load(Ordering::Relaxed);
+        }
+        return words;
+    }
+
+    pub fn set_pixel_words(&self, col_num: usize, row_num: usize, words: &[u64]) {
+        let index = row_num * self.width + col_num;
+        let radiance = &self.radiance[index * 3..index * 3 + 3];
+        let gbuffer = &self.gbuffer[index * GBUFFER_STRIDE..(index + 1) * GBUFFER_STRIDE];
+        for (value, word) in radiance.iter().zip(&words[..3]) {
+            value.store(*word, Ordering::Relaxed);
+        }
+        self.squares[index].store(words[3], Ordering::Relaxed);
+        self.counts[index].store(words[4], Ordering::Relaxed);
+        for (value, word) in gbuffer.iter().zip(&words[5..]) {
+            value.store(*word, Ordering::Relaxed);
+        }
+    }
+
+    // adds the samples of another render of the same image, sums and counts
+    // simply add up so every pixel ends up weighted by its samples. the
+    // g-buffer is taken from the first render that reached the pixel
+    pub fn merge(&self, other: &FrameBuffer) {
+        for index in 0..self.width * self.height {
+            let other_count = other.counts[index].load(Ordering::Relaxed);
+            if other_count == 0 {
+                continue;
+            }
+            if self.counts[index].load(Ordering::Relaxed) == 0 {
+                for n in 0..GBUFFER_STRIDE {
+                    let value = other.gbuffer[index * GBUFFER_STRIDE + n].load(Ordering::Relaxed);
+                    self.gbuffer[index * GBUFFER_STRIDE + n].store(value, Ordering::Relaxed);
+                }
+            }
+            let add = |mine: &AtomicU64, theirs: &AtomicU64| {
+                let sum = f64::from_bits(mine.load(Ordering::Relaxed))
+                    + f64::from_bits(theirs.load(Ordering::Relaxed));
+                mine.store(sum.to_bits(), Ordering::Relaxed);
+            };
+            for n in 0..3 {
+                add(
+                    &self.radiance[index * 3 + n],
+                    &other.radiance[index * 3 + n],
+                );
+            }
+            add(&self.squares[index], &other.squares[index]);
+            self.counts[index].fetch_add(other_count, Ordering::Relaxed);
+        }
+    }
+
+    // rgb8 image of the samples taken per pixel, black for none and
+    // bright yellow for `max_spp`
+    pub fn sample_heatmap(&self, max_spp: usize) -> Vec<u8> {
+        const RAMP: [[f64; 3]; 5] = [
+            [0.0, 0.0, 0.0],
+            [0.2, 0.0, 0.6],
+            [0.8, 0.1, 0.4],
+            [1.0, 0.5, 0.0],
+            [1.0, 1.0, 0.4],
+        ];
+        let mut res = Vec::with_capacity(self.width * self.height * 3);
+        for count in self.counts.iter() {
+            let t = (count.load(Ordering::Relaxed) as f64 / max_spp.max(1) as f64).min(1.0);
+            let pos = t * (RAMP.len() - 1) as f64;
+            let index = (pos as usize).min(RAMP.len() - 2);
+            let frac = pos - index as f64;
+            for (low, high) in RAMP[index].iter().zip(RAMP[index + 1].iter()) {
+                let value = low * (1.0 - frac) + high * frac;
+                res.push((value * 255.0).round() as u8);
+            }
+        }
+        return res;
+    }
+
+    // current estimate of the pixel, black before its first sample
+    pub fn get_color(&self, col_num: usize, row_num: usize) -> [f64; 3] {
+        let count = self.sample_count(col_num, row_num);
+        let mut res = self.radiance_sum(col_num, row_num);
+        if count > 0 {
+            for value in res.iter_mut() {
+                *value /= count as f64;
+            }
+        }
+        return res;
+    }
+
+    pub fn set_gbuffer(&self, col_num: usize, row_num: usize, data: &GBInfo) {
+        let base = (row_num * self.width + col_num) * GBUFFER_STRIDE;
+        let mut values = [0; GBUFFER_STRIDE];
+        values[0] = data.distance.to_bits();
+        for n in 0..3 {
+            values[1 + n] = data.normal.data[n].to_bits();
+            values[4 + n] = data.hit_point.data[n].to_bits();
+        }
+        values[7] = data.hit_obj_id as u64;
+        for (n, value) in values.iter().enumerate() {
+            self.gbuffer[base + n].store(*value, Ordering::Relaxed);
+        }
+    }
+
+    pub fn get_gbuffer(&self, col_num: usize, row_num: usize) -> GBInfo {
+        let base = (row_num * self.width + col_num) * GBUFFER_STRIDE;
+        let value = |n: usize| self.gbuffer[base + n].load(Ordering::Relaxed);
+        let vector = |first: usize| {
+            Vector3::new([
+                f64::from_bits(value(first)),
+                f64::from_bits(value(first + 1)),
+                f64::from_bits(value(first + 2)),
+            ])
+        };
+        return GBInfo {
+            distance: f64::from_bits(value(0)),
+            normal: vector(1),
+            hit_point: vector(4),
+            hit_obj_id: value(7) as usize,
+        };
+    }
+
+    // every value as little endian u64: radiance sums, luminance square sums,
+    // sample counts and g-buffer, in that order
+    pub fn write_raw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for values in [&self.radiance, &self.squares, &self.counts, &self.gbuffer] {
+            for value in values.iter() {
+                out.write_all(&value.load(Ordering::Relaxed).to_le_bytes())?;
+            }
+        }
+        return Ok(());
+    }
+
+    // bytes written by `write_raw`, None if that does not fit in a u64
+    pub fn raw_size(width: usize, height: usize) -> Option<u64> {
+        return (width as u64)
+            .checked_mul(height as u64)?
+            .checked_mul(PIXEL_WORDS as u64 * 8);
+    }
+
+    pub fn read_raw<R: Read>(width: usize, height: usize, input: &mut R) -> io::Result<Self> {
+        let frame = FrameBuffer::new(width, height);
+        for values in [
+            &frame.radiance,
+            &frame.squares,
+            &frame.counts,
+            &frame.gbuffer,
+        ] {
+            for value in values.iter() {
+                let mut bytes = [0; 8];
+                input.read_exact(&mut bytes)?;
+                value.store(u64::from_le_bytes(bytes), Ordering::Relaxed);
+            }
+        }
+        return Ok(frame);
+    }
+
+    // copy into the row containers used by the denoiser
+    pub fn to_pixel_container(&self) -> PixelContainer {
+        let mut data = Vec::with_capacity(self.height);
+        for row_num in 0..self.height {
+            let mut row = RowColPixels::new(self.width);
+            for col_num in 0..self.width {
+                row.set_color(col_num, self.get_color(col_num, row_num));
+            }
+            data.push(row);
+        }
+        return PixelContainer {
+            width: self.width,
+            height: self.height,
+            data,
+        };
+    }
+
+    pub fn to_geometry_buffer(&self) -> GeometryBuffer {
+        let mut data = Vec::with_capacity(self.height);
+        for row_num in 0..self.height {
+            let mut row = RowColGBuffer::new_empty(self.width);
+            for col_num in 0..self.width {
+                row.push_data(self.get_gbuffer(col_num, row_num));
+            }
+            data.push(row);
+        }
+        return GeometryBuffer {
+            height: self.height,
+            data,
+        };
+    }
+}
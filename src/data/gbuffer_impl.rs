@@ -1,28 +1,75 @@
-use crate::settings::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::entity::Ray;
 
-use super::{FilterType, GBInfo, GeometryBuffer, RowColGBuffer};
+use super::{FilterType, GBInfo, GeometryBuffer, HitInfo, RowColGBuffer};
+
+impl GBInfo {
+    // first hit of a camera ray
+    pub fn from_hit(ray_in: &Ray, info: &HitInfo) -> Self {
+        GBInfo {
+            distance: (ray_in.at(info.t) - ray_in.origin).length(),
+            normal: info.normal,
+            hit_point: info.hit_point,
+            hit_obj_id: info.obj_id,
+        }
+    }
+}
 
 impl GeometryBuffer {
-    pub fn new() -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
         // row container by default
-        let mut data = Vec::with_capacity(WINDOW_HEIGHT as usize);
-        for _ in 0..WINDOW_HEIGHT {
-            data.push(RowColGBuffer::new_empty(FilterType::Row))
+        let mut data = Vec::with_capacity(height);
+        for _ in 0..height {
+            data.push(RowColGBuffer::new(width))
         }
-        GeometryBuffer { data }
+        GeometryBuffer { height, data }
     }
 
     pub fn set_row(&mut self, row_num: usize, row_data: RowColGBuffer) {
         self.data[row_num] = row_data;
     }
 
+    // rgb8 image of the normals mapped to [0, 1], black where nothing was hit
+    pub fn to_normal_pixels(&self) -> Vec<u8> {
+        let mut res = Vec::new();
+        for data in self.data.iter().flat_map(|row| row.data.iter()) {
+            if data.normal.length_square() == 0.0 {
+                res.extend_from_slice(&[0, 0, 0]);
+                continue;
+            }
+            for value in data.normal.data.iter() {
+                res.push(((value * 0.5 + 0.5) * 255.0).round() as u8);
+            }
+        }
+        return res;
+    }
+
+    // rgb8 image of the hit distances, near is bright and misses are black
+    pub fn to_depth_pixels(&self) -> Vec<u8> {
+        let max_distance = self
+            .data
+            .iter()
+            .flat_map(|row| row.data.iter())
+            .map(|data| data.distance)
+            .fold(0.0, f64::max);
+        let mut res = Vec::new();
+        for data in self.data.iter().flat_map(|row| row.data.iter()) {
+            let value = if data.distance > 0.0 {
+                (255.0 * (1.0 - 0.9 * data.distance / max_distance)).round() as u8
+            } else {
+                0
+            };
+            res.extend_from_slice(&[value, value, value]);
+        }
+        return res;
+    }
+
     pub fn get_x_or_y(&self, row_col_num: usize, indicator: FilterType) -> RowColGBuffer {
         match indicator {
             FilterType::Row => RowColGBuffer {
                 data: self.data[row_col_num].data.clone(),
             },
             FilterType::Col => {
-                let mut data = Vec::with_capacity(WINDOW_HEIGHT as usize);
+                let mut data = Vec::with_capacity(self.height);
                 for row in self.data.iter() {
                     data.push(*row.get_data(row_col_num));
                 }
@@ -33,14 +80,15 @@ impl GeometryBuffer {
 }
 
 impl RowColGBuffer {
-    pub fn new_empty(filter_type: FilterType) -> Self {
-        match filter_type {
-            FilterType::Row => RowColGBuffer {
-                data: Vec::with_capacity(WINDOW_WIDTH as usize),
-            },
-            FilterType::Col => RowColGBuffer {
-                data: Vec::with_capacity(WINDOW_HEIGHT as usize),
-            },
+    pub fn new(len: usize) -> Self {
+        RowColGBuffer {
+            data: vec![GBInfo::default(); len],
+        }
+    }
+
+    pub fn new_empty(len: usize) -> Self {
+        RowColGBuffer {
+            data: Vec::with_capacity(len),
         }
     }
 
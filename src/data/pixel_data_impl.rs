@@ -1,18 +1,27 @@
-use crate::{
-    settings::{WINDOW_HEIGHT, WINDOW_WIDTH},
-    some_math::{to_u8, Color},
-};
+use crate::some_math::{to_u8, Color};
 
 use super::{FilterType, PixelContainer, RowColPixels};
 
 impl PixelContainer {
-    pub fn new() -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
         // row container by default
-        let mut data = Vec::with_capacity(WINDOW_HEIGHT as usize);
-        for _ in 0..WINDOW_HEIGHT {
-            data.push(RowColPixels::new(FilterType::Row));
+        let mut data = Vec::with_capacity(height);
+        for _ in 0..height {
+            data.push(RowColPixels::new(width));
         }
-        return PixelContainer { data };
+        return PixelContainer {
+            width,
+            height,
+            data,
+        };
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
     }
 
     pub fn get_colors(&self, col_num: usize, row_num: usize) -> [f64; 3] {
@@ -36,7 +45,7 @@ impl PixelContainer {
                 data: self.data[row_col_num].data.clone(),
             },
             FilterType::Col => {
-                let mut data = Vec::with_capacity((WINDOW_HEIGHT * 3) as usize);
+                let mut data = Vec::with_capacity(self.height * 3);
                 for row in self.data.iter() {
                     for num in row.get_color(row_col_num).data.iter() {
                         data.push(*num);
@@ -52,9 +61,9 @@ impl PixelContainer {
     }
 
     pub fn to_pixels(&self) -> Vec<u8> {
-        let mut res = Vec::with_capacity(WINDOW_HEIGHT as usize);
+        let mut res = Vec::with_capacity(self.height);
         for row_pixel_f64 in self.data.iter() {
-            let mut row_pixel_u8 = Vec::with_capacity((WINDOW_WIDTH * 3) as usize);
+            let mut row_pixel_u8 = Vec::with_capacity(self.width * 3);
             for pixel_f64 in row_pixel_f64.data.iter() {
                 row_pixel_u8.push(to_u8(pixel_f64));
             }
@@ -65,14 +74,9 @@ impl PixelContainer {
 }
 
 impl RowColPixels {
-    pub fn new(indicator: FilterType) -> Self {
-        match indicator {
-            FilterType::Row => RowColPixels {
-                data: [0.0; (WINDOW_WIDTH * 3) as usize].to_vec(),
-            },
-            FilterType::Col => RowColPixels {
-                data: [0.0; (WINDOW_HEIGHT * 3) as usize].to_vec(),
-            },
+    pub fn new(len: usize) -> Self {
+        RowColPixels {
+            data: vec![0.0; len * 3],
         }
     }
 
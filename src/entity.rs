@@ -10,6 +10,7 @@ mod panel_impl;
 mod ray_impl;
 mod rectangle_impl;
 mod sphere_impl;
+mod triangle_impl;
 
 #[derive(Clone)]
 pub struct Sphere {
@@ -37,6 +38,17 @@ pub struct Rectangle {
     id: usize,
 }
 
+#[derive(Clone)]
+pub struct Triangle {
+    // counter-clockwise winding faces the geometric normal
+    pub vertices: [Point; 3],
+    // per-vertex normals for smooth shading
+    pub normals: Option<[Vector3; 3]>,
+    pub texcoords: Option<[[f64; 2]; 3]>,
+    pub material: Arc<dyn Material + Send + Sync>,
+    id: usize,
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Ray {
     pub origin: Point,
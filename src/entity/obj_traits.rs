@@ -1,18 +1,33 @@
-use rand::prelude::ThreadRng;
-
 use crate::{
-    data::HitInfo,
+    accel::Aabb,
+    data::{HitInfo, HitRecord},
     material::Light,
+    sampler::Sampler,
     some_math::{Point, Vector3},
 };
 
 use super::Ray;
 
 pub trait Hittable {
-    fn ray_intersect(&self, ray_in: &Ray) -> Option<HitInfo>;
+    // distance only, no normal / material is touched here
+    fn intersect(&self, ray_in: &Ray) -> Option<HitRecord>;
+    // surface data of a hit found by `intersect`
+    fn surface_info(&self, ray_in: &Ray, record: &HitRecord) -> HitInfo;
+    fn bounding_box(&self) -> Aabb;
     fn is_light(&self) -> bool;
+
+    fn ray_intersect(&self, ray_in: &Ray) -> Option<HitInfo> {
+        let record = self.intersect(ray_in)?;
+        return Some(self.surface_info(ray_in, &record));
+    }
+
+    // any hit closer than t_max, no surface data is built
+    fn occluded(&self, ray_in: &Ray, t_max: f64) -> bool {
+        self.intersect(ray_in)
+            .is_some_and(|record| record.t < t_max)
+    }
 }
 
 pub trait HittableLight: Hittable + Light {
-    fn sample_on_light(&self, rng: &mut ThreadRng) -> (Point, Vector3);
+    fn sample_on_light(&self, sampler: &mut dyn Sampler) -> (Point, Vector3);
 }
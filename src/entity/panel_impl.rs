@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
-use rand::{prelude::ThreadRng, Rng};
-
 use crate::{
-    data::HitInfo,
+    accel::Aabb,
+    data::{HitInfo, HitRecord},
     material::{Light, Material},
+    sampler::Sampler,
     some_math::{point_in_2d, Point, Vector3},
 };
 
@@ -30,7 +30,7 @@ impl Panel {
 }
 
 impl Hittable for Panel {
-    fn ray_intersect(&self, ray_in: &Ray) -> Option<HitInfo> {
+    fn intersect(&self, ray_in: &Ray) -> Option<HitRecord> {
         if ray_in.direction * self.normal > 0.0 {
             return None;
         }
@@ -40,20 +40,33 @@ impl Hittable for Panel {
         if t <= 0.0 {
             return None;
         }
-        let hit_point = ray_in.at(t);
-        if point_in_2d(hit_point, self.points, axis) {
-            return Some(HitInfo {
-                hit_point,
+        if point_in_2d(ray_in.at(t), self.points, axis) {
+            return Some(HitRecord {
                 t,
-                normal: self.normal,
-                material: self.material.clone(),
-                obj_id: self.id,
+                ..HitRecord::default()
             });
         } else {
             return None;
         }
     }
 
+    fn surface_info(&self, ray_in: &Ray, record: &HitRecord) -> HitInfo {
+        return HitInfo {
+            hit_point: ray_in.at(record.t),
+            t: record.t,
+            normal: self.normal,
+            material: self.material.clone(),
+            obj_id: self.id,
+            light: None,
+            barycentric: [0.0; 3],
+            uv: [0.0; 2],
+        };
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(&self.points).pad(1e-4)
+    }
+
     fn is_light(&self) -> bool {
         self.material.is_light()
     }
@@ -78,15 +91,19 @@ impl Light for Panel {
 }
 
 impl HittableLight for Panel {
-    fn sample_on_light(&self, rng: &mut ThreadRng) -> (Point, Vector3) {
+    fn sample_on_light(&self, sampler: &mut dyn Sampler) -> (Point, Vector3) {
         let axis = self.normal.get_axis();
+        let sample = sampler.get_2d();
         let mut data = [0.0; 3];
         data[axis] = self.points[0].data[axis];
-        for i in 0..3 {
+        let mut n = 0;
+        for (i, value) in data.iter_mut().enumerate() {
             if i == axis {
                 continue;
             }
-            data[i] = rng.gen_range(self.points[0].data[i]..self.points[1].data[i]);
+            *value = self.points[0].data[i]
+                + sample[n] * (self.points[1].data[i] - self.points[0].data[i]);
+            n += 1;
         }
         return (Point::new(data), self.normal);
     }
@@ -1,10 +1,8 @@
-use std::{
-    f64::{INFINITY, NEG_INFINITY},
-    sync::Arc,
-};
+use std::sync::Arc;
 
 use crate::{
-    data::HitInfo,
+    accel::Aabb,
+    data::{HitInfo, HitRecord},
     material::Material,
     some_math::{order_numbers, Point, Vector3},
     systems::transform::rotate_around_y,
@@ -44,7 +42,7 @@ impl Rectangle {
             return Rectangle {
                 points,
                 angle_rotate_y,
-                trans_points: points.clone(),
+                trans_points: points,
                 material,
                 id,
             };
@@ -53,7 +51,7 @@ impl Rectangle {
 }
 
 impl Hittable for Rectangle {
-    fn ray_intersect(&self, ray_in: &Ray) -> Option<HitInfo> {
+    fn intersect(&self, ray_in: &Ray) -> Option<HitRecord> {
         let mut ray_origin = ray_in.origin;
         let mut ray_direction = ray_in.direction;
         let mut p1 = self.points[0];
@@ -65,8 +63,8 @@ impl Hittable for Rectangle {
             p2 = self.trans_points[1];
         }
         let mut hit_normal = Vector3::default();
-        let mut t_min = NEG_INFINITY;
-        let mut t_max = INFINITY;
+        let mut t_min = f64::NEG_INFINITY;
+        let mut t_max = f64::INFINITY;
         for n in 0..3 {
             let t_0 = (p1.data[n] - ray_origin.data[n]) / ray_direction.data[n];
             let t_1 = (p2.data[n] - ray_origin.data[n]) / ray_direction.data[n];
@@ -86,20 +84,49 @@ impl Hittable for Rectangle {
         if t_min > t_max || t_min <= 0.0 {
             return None;
         }
-        let hit_point = ray_in.at(t_min);
+        return Some(HitRecord {
+            t: t_min,
+            local: hit_normal.data,
+        });
+    }
+
+    fn surface_info(&self, ray_in: &Ray, record: &HitRecord) -> HitInfo {
+        // the face normal was found in the box frame
+        let hit_normal = Vector3::new(record.local);
         let normal;
         if let Some(angle) = self.angle_rotate_y {
             normal = rotate_around_y(hit_normal, angle);
         } else {
             normal = hit_normal;
         }
-        return Some(HitInfo {
-            hit_point,
-            t: t_min,
+        return HitInfo {
+            hit_point: ray_in.at(record.t),
+            t: record.t,
             normal,
             material: self.material.clone(),
             obj_id: self.id,
-        });
+            light: None,
+            barycentric: [0.0; 3],
+            uv: [0.0; 2],
+        };
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        match self.angle_rotate_y {
+            Some(angle) => {
+                // corners of the box in its own frame, rotated back into the world
+                let mut corners = [Point::default(); 8];
+                for (n, corner) in corners.iter_mut().enumerate() {
+                    let mut data = [0.0; 3];
+                    for (axis, value) in data.iter_mut().enumerate() {
+                        *value = self.trans_points[(n >> axis) & 1].data[axis];
+                    }
+                    *corner = rotate_around_y(Point::new(data), angle);
+                }
+                Aabb::from_points(&corners)
+            }
+            None => Aabb::from_points(&self.points),
+        }
     }
 
     fn is_light(&self) -> bool {
@@ -1,6 +1,11 @@
 use std::sync::Arc;
 
-use crate::{data::HitInfo, material::Material, some_math::Point};
+use crate::{
+    accel::Aabb,
+    data::{HitInfo, HitRecord},
+    material::Material,
+    some_math::{Point, Vector3},
+};
 
 use super::{obj_traits::Hittable, Ray, Sphere};
 
@@ -21,7 +26,7 @@ impl Sphere {
 }
 
 impl Hittable for Sphere {
-    fn ray_intersect(&self, ray_in: &Ray) -> Option<HitInfo> {
+    fn intersect(&self, ray_in: &Ray) -> Option<HitRecord> {
         let oc = ray_in.origin - self.center;
         let a = ray_in.direction * ray_in.direction;
         let b = 2.0 * ray_in.direction * oc;
@@ -32,27 +37,40 @@ impl Hittable for Sphere {
         }
         let t = (-b - indicator.sqrt()) / (2.0 * a);
         if t > 0.0 {
-            let hit_point = ray_in.at(t);
-            let normal = (hit_point - self.center).normalize();
-            return Some(HitInfo {
-                hit_point,
+            return Some(HitRecord {
                 t,
-                normal,
-                material: self.material.clone(),
-                obj_id: self.id,
+                ..HitRecord::default()
             });
-        } else {
-            let t = (-b + indicator.sqrt()) / (2.0 * a);
-            let hit_point = ray_in.at(t);
-            let normal = (hit_point - self.center).normalize();
-            return Some(HitInfo {
-                hit_point,
+        }
+        // origin inside the sphere, take the far side
+        let t = (-b + indicator.sqrt()) / (2.0 * a);
+        if t > 0.0 {
+            return Some(HitRecord {
                 t,
-                normal,
-                material: self.material.clone(),
-                obj_id: self.id,
+                ..HitRecord::default()
             });
         }
+        return None;
+    }
+
+    fn surface_info(&self, ray_in: &Ray, record: &HitRecord) -> HitInfo {
+        let hit_point = ray_in.at(record.t);
+        let normal = (hit_point - self.center).normalize();
+        return HitInfo {
+            hit_point,
+            t: record.t,
+            normal,
+            material: self.material.clone(),
+            obj_id: self.id,
+            light: None,
+            barycentric: [0.0; 3],
+            uv: [0.0; 2],
+        };
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = Vector3::new([self.radius.abs(); 3]);
+        Aabb::new(self.center - r, self.center + r)
     }
 
     fn is_light(&self) -> bool {
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use crate::{
+    accel::Aabb,
+    data::{HitInfo, HitRecord},
+    material::{Light, Material},
+    sampler::Sampler,
+    some_math::{Color, Point, Vector3},
+};
+
+use super::{
+    obj_traits::{Hittable, HittableLight},
+    Ray, Triangle,
+};
+
+// hits closer than this are treated as self-intersection
+const T_EPSILON: f64 = 1e-6;
+
+impl Triangle {
+    pub fn new(
+        vertices: [Point; 3],
+        normals: Option<[Vector3; 3]>,
+        texcoords: Option<[[f64; 2]; 3]>,
+        material: Arc<dyn Material + Send + Sync>,
+        id: usize,
+    ) -> Self {
+        Triangle {
+            vertices,
+            normals,
+            texcoords,
+            material,
+            id,
+        }
+    }
+
+    pub fn geometric_normal(&self) -> Vector3 {
+        (self.vertices[1] - self.vertices[0])
+            .cross_product(self.vertices[2] - self.vertices[0])
+            .normalize()
+    }
+
+    pub fn area(&self) -> f64 {
+        (self.vertices[1] - self.vertices[0])
+            .cross_product(self.vertices[2] - self.vertices[0])
+            .length()
+            / 2.0
+    }
+
+    fn shading_normal(&self, barycentric: [f64; 3]) -> Vector3 {
+        match self.normals {
+            Some(normals) => (barycentric[0] * normals[0]
+                + barycentric[1] * normals[1]
+                + barycentric[2] * normals[2])
+                .normalize(),
+            None => self.geometric_normal(),
+        }
+    }
+
+    fn uv(&self, barycentric: [f64; 3]) -> [f64; 2] {
+        match self.texcoords {
+            Some(texcoords) => {
+                let mut uv = [0.0; 2];
+                for (n, value) in uv.iter_mut().enumerate() {
+                    *value = barycentric[0] * texcoords[0][n]
+                        + barycentric[1] * texcoords[1][n]
+                        + barycentric[2] * texcoords[2][n];
+                }
+                uv
+            }
+            None => [barycentric[1], barycentric[2]],
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn intersect(&self, ray_in: &Ray) -> Option<HitRecord> {
+        // watertight ray / triangle test, Woop et al. 2013
+        // shear the triangle into ray space so that shared edges give
+        // consistent results for neighboring triangles
+        let dir = ray_in.direction;
+        let mut kz = 0;
+        for n in 1..3 {
+            if dir.data[n].abs() > dir.data[kz].abs() {
+                kz = n;
+            }
+        }
+        let mut kx = (kz + 1) % 3;
+        let mut ky = (kx + 1) % 3;
+        if dir.data[kz] < 0.0 {
+            std::mem::swap(&mut kx, &mut ky);
+        }
+        let sx = dir.data[kx] / dir.data[kz];
+        let sy = dir.data[ky] / dir.data[kz];
+        let sz = 1.0 / dir.data[kz];
+
+        let a = self.vertices[0] - ray_in.origin;
+        let b = self.vertices[1] - ray_in.origin;
+        let c = self.vertices[2] - ray_in.origin;
+        let ax = a.data[kx] - sx * a.data[kz];
+        let ay = a.data[ky] - sy * a.data[kz];
+        let bx = b.data[kx] - sx * b.data[kz];
+        let by = b.data[ky] - sy * b.data[kz];
+        let cx = c.data[kx] - sx * c.data[kz];
+        let cy = c.data[ky] - sy * c.data[kz];
+
+        // scaled barycentric coordinates, edge functions of the sheared triangle
+        let u = cx * by - cy * bx;
+        let v = ax * cy - ay * cx;
+        let w = bx * ay - by * ax;
+        if (u < 0.0 || v < 0.0 || w < 0.0) && (u > 0.0 || v > 0.0 || w > 0.0) {
+            return None;
+        }
+        let det = u + v + w;
+        if det == 0.0 {
+            return None;
+        }
+        let az = sz * a.data[kz];
+        let bz = sz * b.data[kz];
+        let cz = sz * c.data[kz];
+        let t = (u * az + v * bz + w * cz) / det;
+        if t <= T_EPSILON {
+            return None;
+        }
+
+        return Some(HitRecord {
+            t,
+            local: [u / det, v / det, w / det],
+        });
+    }
+
+    fn surface_info(&self, _ray_in: &Ray, record: &HitRecord) -> HitInfo {
+        let barycentric = record.local;
+        let hit_point = barycentric[0] * self.vertices[0]
+            + barycentric[1] * self.vertices[1]
+            + barycentric[2] * self.vertices[2];
+        return HitInfo {
+            hit_point,
+            t: record.t,
+            normal: self.shading_normal(barycentric),
+            material: self.material.clone(),
+            obj_id: self.id,
+            light: None,
+            barycentric,
+            uv: self.uv(barycentric),
+        };
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(&self.vertices).pad(1e-4)
+    }
+
+    fn is_light(&self) -> bool {
+        self.material.is_light()
+    }
+}
+
+impl Light for Triangle {
+    fn get_pdf_mul(&self) -> f64 {
+        self.area()
+    }
+
+    fn get_light_color(&self) -> Color {
+        self.material.emit()
+    }
+}
+
+impl HittableLight for Triangle {
+    fn sample_on_light(&self, sampler: &mut dyn Sampler) -> (Point, Vector3) {
+        // uniform sampling over the triangle area
+        let sample = sampler.get_2d();
+        let su = sample[0].sqrt();
+        let b1 = sample[1] * su;
+        let b0 = 1.0 - su;
+        let b2 = 1.0 - b0 - b1;
+        let point = b0 * self.vertices[0] + b1 * self.vertices[1] + b2 * self.vertices[2];
+        return (point, self.geometric_normal());
+    }
+}
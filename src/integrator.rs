@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use crate::{
+    accel::Bvh,
+    data::GBInfo,
+    entity::{obj_traits::HittableLight, Ray},
+    sampler::Sampler,
+    some_math::Color,
+};
+
+mod ambient_occlusion_impl;
+mod debug_impl;
+mod direct_impl;
+mod kind_impl;
+mod path_impl;
+mod whitted_impl;
+
+// turns a camera ray into the value accumulated for its pixel, radiance for
+// the light transport integrators and a false color for the debug views
+pub trait Integrator {
+    // `gbuffer_data` is filled from the first hit when given
+    fn radiance(
+        &self,
+        ray_in: &Ray,
+        objects: &Bvh,
+        lights: &[Arc<dyn HittableLight + Send + Sync>],
+        sampler: &mut dyn Sampler,
+        gbuffer_data: Option<&mut GBInfo>,
+    ) -> Color;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegratorKind {
+    Path,
+    Direct,
+    AmbientOcclusion,
+    Whitted,
+    Normals,
+    Depth,
+    ObjectId,
+    Albedo,
+}
+
+// unidirectional path tracer with light sampling, mis and russian roulette
+#[derive(Debug, Clone, Copy)]
+pub struct PathTracer {
+    max_depth: i32,
+    roulette_depth: i32,
+}
+
+// emission plus direct lighting at the first hit, no indirect bounces
+#[derive(Debug, Clone, Copy)]
+pub struct DirectLighting;
+
+// fraction of the cosine-weighted hemisphere that is open within a tenth of
+// the scene size
+#[derive(Debug, Clone, Copy)]
+pub struct AmbientOcclusion;
+
+// whitted-style recursive ray tracing: mirrors reflect, glass traces both its
+// reflected and refracted ray weighted by the fresnel term, and every chain
+// ends in direct lighting on the first non-specular surface
+#[derive(Debug, Clone, Copy)]
+pub struct Whitted {
+    max_depth: i32,
+}
+
+// first hit visualisations, black where nothing was hit
+#[derive(Debug, Clone, Copy)]
+pub struct DebugView {
+    channel: DebugChannel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugChannel {
+    Normals,
+    Depth,
+    ObjectId,
+    Albedo,
+}
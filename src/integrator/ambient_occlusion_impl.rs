@@ -0,0 +1,53 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{
+    accel::Bvh,
+    data::GBInfo,
+    entity::{obj_traits::HittableLight, Ray},
+    sampler::Sampler,
+    some_math::{Color, Vector3},
+    systems::transform::rotate_vec_given_normal,
+};
+
+use super::{AmbientOcclusion, Integrator};
+
+// occlusion radius as a fraction of the scene bounding box diagonal
+const RADIUS_SCALE: f64 = 0.1;
+
+impl Integrator for AmbientOcclusion {
+    fn radiance(
+        &self,
+        ray_in: &Ray,
+        objects: &Bvh,
+        _lights: &[Arc<dyn HittableLight + Send + Sync>],
+        sampler: &mut dyn Sampler,
+        gbuffer_data: Option<&mut GBInfo>,
+    ) -> Color {
+        let info = match objects.ray_hit(ray_in, false) {
+            Some(info) => info,
+            None => return Color::BLACK,
+        };
+        if let Some(gbuffer_data) = gbuffer_data {
+            *gbuffer_data = GBInfo::from_hit(ray_in, &info);
+        }
+        let mut normal = info.normal;
+        if ray_in.direction * normal > 0.0 {
+            normal = (-1.0) * normal;
+        }
+        // cosine-weighted, so every open direction counts the same
+        let [a, b] = sampler.get_2d();
+        let sin_theta = a.sqrt();
+        let cos_theta = (1.0 - a).sqrt();
+        let local = Vector3::new([
+            sin_theta * (2.0 * PI * b).cos(),
+            cos_theta,
+            sin_theta * (2.0 * PI * b).sin(),
+        ]);
+        let dir = rotate_vec_given_normal(&local, &normal);
+        let radius = objects.bounds().extent().length() * RADIUS_SCALE;
+        if objects.occluded(&Ray::new(info.hit_point, dir), radius, false) {
+            return Color::BLACK;
+        }
+        return Color::new([1.0; 3]);
+    }
+}
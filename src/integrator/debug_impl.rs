@@ -0,0 +1,62 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{
+    accel::Bvh,
+    data::GBInfo,
+    entity::{obj_traits::HittableLight, Ray},
+    sampler::Sampler,
+    some_math::Color,
+};
+
+use super::{DebugChannel, DebugView, Integrator};
+
+impl Integrator for DebugView {
+    fn radiance(
+        &self,
+        ray_in: &Ray,
+        objects: &Bvh,
+        _lights: &[Arc<dyn HittableLight + Send + Sync>],
+        _sampler: &mut dyn Sampler,
+        gbuffer_data: Option<&mut GBInfo>,
+    ) -> Color {
+        let info = match objects.ray_hit(ray_in, false) {
+            Some(info) => info,
+            None => return Color::BLACK,
+        };
+        if let Some(gbuffer_data) = gbuffer_data {
+            *gbuffer_data = GBInfo::from_hit(ray_in, &info);
+        }
+        match self.channel {
+            // mapped to [0, 1] like the g-buffer normal image
+            DebugChannel::Normals => {
+                return info.normal * 0.5 + Color::new([0.5; 3]);
+            }
+            // near is bright, fades to 0.1 at the scene corner farthest from the camera
+            DebugChannel::Depth => {
+                let distance = (info.hit_point - ray_in.origin).length();
+                let bounds = objects.bounds();
+                let mut scale: f64 = 0.0;
+                for corner in 0..8 {
+                    let mut point = bounds.min;
+                    for axis in 0..3 {
+                        if corner & (1 << axis) != 0 {
+                            point.data[axis] = bounds.max.data[axis];
+                        }
+                    }
+                    scale = scale.max((point - ray_in.origin).length());
+                }
+                let value = (1.0 - 0.9 * distance / scale).max(0.1);
+                return Color::new([value; 3]);
+            }
+            // golden ratio steps around the hue circle keep neighbouring ids apart
+            DebugChannel::ObjectId => {
+                let hue = (info.obj_id as f64 * 0.618_033_988_75).fract();
+                let channel = |offset: f64| 0.5 + 0.5 * (2.0 * PI * (hue + offset)).cos();
+                return Color::new([channel(0.0), channel(1.0 / 3.0), channel(2.0 / 3.0)]);
+            }
+            DebugChannel::Albedo => {
+                return info.material.albedo();
+            }
+        }
+    }
+}
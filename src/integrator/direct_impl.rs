@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use crate::{
+    accel::Bvh,
+    data::GBInfo,
+    entity::{obj_traits::HittableLight, Ray},
+    sampler::Sampler,
+    some_math::Color,
+    systems::path_tracing::{direct_lighting, emitted},
+};
+
+use super::{DirectLighting, Integrator};
+
+impl Integrator for DirectLighting {
+    fn radiance(
+        &self,
+        ray_in: &Ray,
+        objects: &Bvh,
+        lights: &[Arc<dyn HittableLight + Send + Sync>],
+        sampler: &mut dyn Sampler,
+        gbuffer_data: Option<&mut GBInfo>,
+    ) -> Color {
+        let info = match objects.ray_hit(ray_in, false) {
+            Some(info) => info,
+            None => return Color::BLACK,
+        };
+        if let Some(gbuffer_data) = gbuffer_data {
+            *gbuffer_data = GBInfo::from_hit(ray_in, &info);
+        }
+        let radiance = emitted(ray_in, &info, lights, None);
+        if info.material.is_light() {
+            return radiance;
+        }
+        return radiance + direct_lighting(ray_in, &info, objects, lights, sampler);
+    }
+}
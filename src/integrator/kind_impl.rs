@@ -0,0 +1,65 @@
+use crate::settings::RenderSettings;
+
+use super::{
+    AmbientOcclusion, DebugChannel, DebugView, DirectLighting, Integrator, IntegratorKind,
+    PathTracer, Whitted,
+};
+
+impl IntegratorKind {
+    pub fn build(self, settings: &RenderSettings) -> Box<dyn Integrator + Send + Sync> {
+        match self {
+            IntegratorKind::Path => Box::new(PathTracer {
+                max_depth: settings.ray_depth,
+                roulette_depth: settings.roulette_depth,
+            }),
+            IntegratorKind::Direct => Box::new(DirectLighting),
+            IntegratorKind::AmbientOcclusion => Box::new(AmbientOcclusion),
+            IntegratorKind::Whitted => Box::new(Whitted {
+                max_depth: settings.ray_depth,
+            }),
+            IntegratorKind::Normals => Box::new(DebugView {
+                channel: DebugChannel::Normals,
+            }),
+            IntegratorKind::Depth => Box::new(DebugView {
+                channel: DebugChannel::Depth,
+            }),
+            IntegratorKind::ObjectId => Box::new(DebugView {
+                channel: DebugChannel::ObjectId,
+            }),
+            IntegratorKind::Albedo => Box::new(DebugView {
+                channel: DebugChannel::Albedo,
+            }),
+        }
+    }
+
+    pub const ALL: [IntegratorKind; 8] = [
+        IntegratorKind::Path,
+        IntegratorKind::Direct,
+        IntegratorKind::AmbientOcclusion,
+        IntegratorKind::Whitted,
+        IntegratorKind::Normals,
+        IntegratorKind::Depth,
+        IntegratorKind::ObjectId,
+        IntegratorKind::Albedo,
+    ];
+
+    pub fn from_label(label: &str) -> Option<IntegratorKind> {
+        return IntegratorKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.label() == label);
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            IntegratorKind::Path => "path",
+            IntegratorKind::Direct => "direct",
+            IntegratorKind::AmbientOcclusion => "ao",
+            IntegratorKind::Whitted => "whitted",
+            IntegratorKind::Normals => "normals",
+            IntegratorKind::Depth => "depth",
+            IntegratorKind::ObjectId => "object-id",
+            IntegratorKind::Albedo => "albedo",
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use crate::{
+    accel::Bvh,
+    data::GBInfo,
+    entity::{obj_traits::HittableLight, Ray},
+    sampler::Sampler,
+    some_math::Color,
+    systems::path_tracing::shade,
+};
+
+use super::{Integrator, PathTracer};
+
+impl Integrator for PathTracer {
+    fn radiance(
+        &self,
+        ray_in: &Ray,
+        objects: &Bvh,
+        lights: &[Arc<dyn HittableLight + Send + Sync>],
+        sampler: &mut dyn Sampler,
+        gbuffer_data: Option<&mut GBInfo>,
+    ) -> Color {
+        return shade(
+            ray_in,
+            objects,
+            lights,
+            self.max_depth,
+            self.roulette_depth,
+            sampler,
+            gbuffer_data,
+        );
+    }
+}
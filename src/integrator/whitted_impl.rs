@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use crate::{
+    accel::Bvh,
+    data::GBInfo,
+    entity::{obj_traits::HittableLight, Ray},
+    sampler::Sampler,
+    some_math::Color,
+    systems::path_tracing::{direct_lighting, emitted},
+};
+
+use super::{Integrator, Whitted};
+
+// branches carrying less than this are not traced, glass splits every ray in
+// two and most of them end up contributing nothing
+const MIN_THROUGHPUT: f64 = 1e-3;
+
+impl Whitted {
+    // radiance along `ray` after `depth` delta bounces that scaled it by `throughput`
+    #[allow(clippy::too_many_arguments)]
+    fn trace(
+        &self,
+        ray: &Ray,
+        objects: &Bvh,
+        lights: &[Arc<dyn HittableLight + Send + Sync>],
+        sampler: &mut dyn Sampler,
+        gbuffer_data: Option<&mut GBInfo>,
+        depth: i32,
+        throughput: Color,
+    ) -> Color {
+        let info = match objects.ray_hit(ray, false) {
+            Some(info) => info,
+            None => return Color::BLACK,
+        };
+        if let Some(gbuffer_data) = gbuffer_data {
+            *gbuffer_data = GBInfo::from_hit(ray, &info);
+        }
+        // only delta bounces get here, their emission is never light sampled
+        let mut radiance = emitted(ray, &info, lights, None);
+        if info.material.is_light() {
+            return radiance;
+        }
+        if info.material.flags().is_non_specular() {
+            return radiance + direct_lighting(ray, &info, objects, lights, sampler);
+        }
+        if depth >= self.max_depth {
+            return radiance;
+        }
+        // both glass rays are followed, weighted by the fresnel term
+        let wo = (-1.0) * ray.direction;
+        for lobe in info.material.delta_lobes(&wo, &info.normal) {
+            let weight = lobe.weight * lobe.pdf;
+            let next = throughput.naive_mul(weight);
+            if next.data.iter().all(|&value| value < MIN_THROUGHPUT) {
+                continue;
+            }
+            let ray = Ray::new(info.hit_point, lobe.wi);
+            radiance +=
+                weight.naive_mul(self.trace(&ray, objects, lights, sampler, None, depth + 1, next));
+        }
+        return radiance;
+    }
+}
+
+impl Integrator for Whitted {
+    fn radiance(
+        &self,
+        ray_in: &Ray,
+        objects: &Bvh,
+        lights: &[Arc<dyn HittableLight + Send + Sync>],
+        sampler: &mut dyn Sampler,
+        gbuffer_data: Option<&mut GBInfo>,
+    ) -> Color {
+        return self.trace(
+            ray_in,
+            objects,
+            lights,
+            sampler,
+            gbuffer_data,
+            0,
+            Color::new([1.0; 3]),
+        );
+    }
+}
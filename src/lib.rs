@@ -0,0 +1,40 @@
+//
+//      @author: 张峻魁 | Junkui Zhang
+//      @email:  junkuizhangchina@gmail.com
+//      @date:   2021, Jul
+//
+#![allow(clippy::needless_return)]
+
+pub mod accel;
+pub mod camera;
+pub mod checkpoint;
+pub mod data;
+pub mod entity;
+pub mod integrator;
+pub mod material;
+pub mod sampler;
+pub mod scene;
+pub mod scheduler;
+pub mod settings;
+pub mod some_math;
+pub mod systems;
+pub mod tile;
+pub mod world;
+
+pub use accel::{Bvh, BvhStats};
+pub use camera::Camera;
+pub use checkpoint::{CheckpointError, CheckpointInfo};
+pub use data::{BsdfSample, GeometryBuffer, PixelContainer};
+pub use entity::obj_traits::{Hittable, HittableLight};
+pub use integrator::{Integrator, IntegratorKind};
+pub use material::{BsdfFlags, Light, Material};
+pub use sampler::{Sampler, SamplerKind};
+pub use scene::{MeshTransform, SceneDesc, SceneError};
+pub use scheduler::{CancelToken, Scheduler, SchedulerStats};
+pub use settings::{
+    AdaptiveSettings, CheckpointSettings, DenoiseStage, OutputSettings, RenderBudget,
+    RenderSettings,
+};
+pub use systems::image_process::denoise;
+pub use tile::{Tile, TileOrder};
+pub use world::{RemoteError, World};
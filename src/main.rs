@@ -3,19 +3,92 @@
 //      @email:  junkuizhangchina@gmail.com
 //      @date:   2021, Jul
 //
-use world::World;
+#![allow(clippy::needless_return)]
 
-mod camera;
-mod data;
-mod entity;
-mod material;
-mod settings;
-mod some_math;
-mod systems;
-mod world;
+use std::process;
+
+use clap::Parser;
+use cli::Args;
+use ray_tracing_demo::World;
+
+mod cli;
 
 fn main() {
-    let mut world = World::new();
-    world.default_scene();
-    world.run();
+    let args = Args::parse();
+    if let Err(err) = args.validate() {
+        err.exit();
+    }
+    if let Some(addr) = &args.worker {
+        let thread_num = args.render_settings().thread_num;
+        if let Err(err) = World::work(addr.as_str(), args.scene.as_ref(), thread_num) {
+            eprintln!("error: worker: {}", err);
+            process::exit(1);
+        }
+        return;
+    }
+    let mut world = World::new(args.render_settings());
+    if !args.merge.is_empty() {
+        match world.load_merged(&args.merge) {
+            Ok(info) => println!(
+                "==> Merged {} partial renders into {}x{}",
+                args.merge.len(),
+                info.width,
+                info.height
+            ),
+            Err(err) => {
+                eprintln!("error: cannot merge: {}", err);
+                process::exit(1);
+            }
+        }
+        if let Err(err) = world.finish(&args.output_settings()) {
+            eprintln!("error: cannot write image: {}", err);
+            process::exit(1);
+        }
+        return;
+    }
+    // the first ctrl-c stops the render and keeps what is done, the second one
+    // exits right away
+    let cancel = world.cancel_token();
+    let handler = ctrlc::set_handler(move || {
+        if cancel.is_cancelled() {
+            process::exit(130);
+        }
+        eprintln!("Interrupted, finishing the running tiles, press ctrl-c again to quit");
+        cancel.cancel();
+    });
+    if let Err(err) = handler {
+        eprintln!("warning: cannot install the ctrl-c handler: {}", err);
+    }
+    match &args.scene {
+        Some(path) => {
+            if let Err(err) = world.load_scene(path) {
+                eprintln!("error: {}: {}", path.display(), err);
+                process::exit(1);
+            }
+        }
+        None => world.default_scene(),
+    }
+    if let Some(path) = &args.resume {
+        match world.resume(path) {
+            Ok(samples) => println!("==> Resuming from {} spp", samples),
+            Err(err) => {
+                eprintln!("error: {}: {}", path.display(), err);
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(addr) = &args.serve {
+        match world.serve_workers(addr.as_str()) {
+            Ok(local_addr) => println!("==> Waiting for workers on {}", local_addr),
+            Err(err) => {
+                eprintln!("error: cannot listen on {}: {}", addr, err);
+                process::exit(1);
+            }
+        }
+    }
+    if let Err(err) = world.run(&args.output_settings()) {
+        eprintln!("error: cannot write image: {}", err);
+        process::exit(1);
+    }
+    world.release_workers();
 }
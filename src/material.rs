@@ -1,13 +1,12 @@
-use rand::prelude::ThreadRng;
-
 use crate::{
-    data::ScatterInfo,
-    entity::Ray,
+    data::BsdfSample,
+    sampler::Sampler,
     some_math::{Color, Vector3},
 };
 
 mod diffuse_light_impl;
 mod diffuse_mat_impl;
+mod flags_impl;
 mod glass_impl;
 mod metal_impl;
 
@@ -25,6 +24,7 @@ pub struct DiffuseLight {
 #[derive(Debug, Clone, Copy)]
 pub struct Metal {
     pub color: Color,
+    // perceptual roughness, 0 is a perfect mirror
     fuzz: f64,
 }
 
@@ -35,15 +35,51 @@ pub struct Glass {
     eta: f64,
 }
 
+// set of bsdf lobes, see the associated constants
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BsdfFlags(u8);
+
+// directions point away from the surface: `wo` towards the viewer, `wi`
+// towards the light. `normal` is the geometric normal as stored on the hit,
+// it may face either side of the surface
 pub trait Material {
-    fn scatter(&self, ray_in: &Ray, hit_normal: &Vector3, rng: &mut ThreadRng) -> ScatterInfo;
+    // bsdf value for a pair of directions, black for delta lobes
+    fn eval(&self, wo: &Vector3, wi: &Vector3, normal: &Vector3) -> Color;
+    // solid angle density of `sample` returning `wi`, zero for delta lobes
+    fn pdf(&self, wo: &Vector3, wi: &Vector3, normal: &Vector3) -> f64;
+    // None when the path ends here
+    fn sample(
+        &self,
+        wo: &Vector3,
+        normal: &Vector3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<BsdfSample>;
+    // all delta lobes at once, each as `sample` returns it with its
+    // probability as pdf. empty without delta lobes
+    fn delta_lobes(&self, wo: &Vector3, normal: &Vector3) -> Vec<BsdfSample>;
+    // every lobe the material has
+    fn flags(&self) -> BsdfFlags;
+    // base color, shown by the albedo debug view
+    fn albedo(&self) -> Color;
     fn emit(&self) -> Color;
-    fn get_color(&self) -> Color;
     fn is_light(&self) -> bool;
-    fn get_fresnel(&self, ray_in_dir: &Vector3, hit_normal: &Vector3) -> f64;
 }
 
 pub trait Light {
+    // area of the light, the inverse of the pdf of `sample_on_light`
     fn get_pdf_mul(&self) -> f64;
     fn get_light_color(&self) -> Color;
 }
+
+// normal flipped onto the side of `wo`
+fn facing_normal(wo: &Vector3, normal: &Vector3) -> Vector3 {
+    if (*wo) * (*normal) < 0.0 {
+        return (-1.0) * (*normal);
+    }
+    return *normal;
+}
+
+// schlick's approximation, `f0` is the reflectance at normal incidence
+fn schlick(f0: f64, cos_theta: f64) -> f64 {
+    return f0 + (1.0 - f0) * (1.0 - cos_theta).max(0.0).powi(5);
+}
@@ -1,12 +1,10 @@
-use rand::prelude::ThreadRng;
-
 use crate::{
-    data::ScatterInfo,
-    entity::Ray,
+    data::BsdfSample,
+    sampler::Sampler,
     some_math::{Color, Vector3},
 };
 
-use super::{DiffuseLight, Material};
+use super::{BsdfFlags, DiffuseLight, Material};
 
 impl DiffuseLight {
     pub fn new(color: Color) -> Self {
@@ -14,28 +12,42 @@ impl DiffuseLight {
     }
 }
 
+// lights only emit, paths end on them
 impl Material for DiffuseLight {
-    fn scatter(&self, _ray_in: &Ray, hit_normal: &Vector3, _rng: &mut ThreadRng) -> ScatterInfo {
-        ScatterInfo {
-            scatter_dir: *hit_normal,
-            color: self.color,
-            pdf: 0.0,
-        }
+    fn eval(&self, _wo: &Vector3, _wi: &Vector3, _normal: &Vector3) -> Color {
+        Color::BLACK
     }
 
-    fn emit(&self) -> Color {
+    fn pdf(&self, _wo: &Vector3, _wi: &Vector3, _normal: &Vector3) -> f64 {
+        0.0
+    }
+
+    fn sample(
+        &self,
+        _wo: &Vector3,
+        _normal: &Vector3,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<BsdfSample> {
+        None
+    }
+
+    fn delta_lobes(&self, _wo: &Vector3, _normal: &Vector3) -> Vec<BsdfSample> {
+        Vec::new()
+    }
+
+    fn flags(&self) -> BsdfFlags {
+        BsdfFlags::NONE
+    }
+
+    fn albedo(&self) -> Color {
         self.color
     }
 
-    fn get_color(&self) -> Color {
+    fn emit(&self) -> Color {
         self.color
     }
 
     fn is_light(&self) -> bool {
         true
     }
-
-    fn get_fresnel(&self, _ray_in_dir: &Vector3, _hit_normal: &Vector3) -> f64 {
-        1.0
-    }
 }
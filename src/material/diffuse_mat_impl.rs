@@ -1,15 +1,13 @@
 use std::f64::consts::PI;
 
-use rand::{prelude::ThreadRng, Rng};
-
 use crate::{
-    data::ScatterInfo,
-    entity::Ray,
+    data::BsdfSample,
+    sampler::Sampler,
     some_math::{Color, Vector3},
     systems::transform::rotate_vec_given_normal,
 };
 
-use super::{DiffuseMat, Material};
+use super::{facing_normal, BsdfFlags, DiffuseMat, Material};
 
 impl DiffuseMat {
     pub fn new(color: Color) -> Self {
@@ -19,46 +17,67 @@ impl DiffuseMat {
     }
 }
 
+// lambertian reflection
 impl Material for DiffuseMat {
-    fn scatter(&self, _ray_in: &Ray, hit_normal: &Vector3, rng: &mut ThreadRng) -> ScatterInfo {
+    fn eval(&self, wo: &Vector3, wi: &Vector3, normal: &Vector3) -> Color {
+        if (*wi) * facing_normal(wo, normal) <= 0.0 {
+            return Color::BLACK;
+        }
+        return self.diffuse_color / PI;
+    }
+
+    fn pdf(&self, wo: &Vector3, wi: &Vector3, normal: &Vector3) -> f64 {
+        return ((*wi) * facing_normal(wo, normal)).max(0.0) / PI;
+    }
+
+    fn sample(
+        &self,
+        wo: &Vector3,
+        normal: &Vector3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<BsdfSample> {
         // impl cosine-weighted sampling
-        let a: f64 = rng.gen_range(0.0..1.0);
-        let b: f64 = rng.gen_range(0.0..1.0);
+        let [a, b] = sampler.get_2d();
         let sin_theta = a.sqrt();
         let cos_theta = (1.0 - a).sqrt();
         let sin_phi = (2.0 * PI * b).sin();
         let cos_phi = (2.0 * PI * b).cos();
         let pdf = cos_theta / PI;
+        if pdf <= 0.0 {
+            return None;
+        }
         let x = sin_theta * cos_phi;
         let z = sin_theta * sin_phi;
         let y = cos_theta;
 
-        // let scatter_dir = generate_unit_vec_hemisphere(hit_normal, rng);
-        // let pdf = 1.0 / (4.0 * PI);
-
         let temp_dir = Vector3::new([x, y, z]);
-        let scatter_dir = rotate_vec_given_normal(&temp_dir, hit_normal);
-        return ScatterInfo {
-            scatter_dir,
-            color: self.diffuse_color,
+        let wi = rotate_vec_given_normal(&temp_dir, &facing_normal(wo, normal));
+        // albedo / pi * cos / pdf
+        return Some(BsdfSample {
+            wi,
+            weight: self.diffuse_color,
             pdf,
-        };
+            flags: BsdfFlags::DIFFUSE | BsdfFlags::REFLECTION,
+        });
     }
 
-    fn emit(&self) -> Color {
-        Color::BLACK
+    fn delta_lobes(&self, _wo: &Vector3, _normal: &Vector3) -> Vec<BsdfSample> {
+        Vec::new()
+    }
+
+    fn flags(&self) -> BsdfFlags {
+        BsdfFlags::DIFFUSE | BsdfFlags::REFLECTION
     }
 
-    fn get_color(&self) -> Color {
+    fn albedo(&self) -> Color {
         self.diffuse_color
     }
 
-    fn is_light(&self) -> bool {
-        false
+    fn emit(&self) -> Color {
+        Color::BLACK
     }
 
-    fn get_fresnel(&self, ray_in_dir: &Vector3, hit_normal: &Vector3) -> f64 {
-        let f0 = 0.45;
-        return f0 + (1.0 - f0) * ((*ray_in_dir) * (*hit_normal)).abs().powi(5);
+    fn is_light(&self) -> bool {
+        false
     }
 }
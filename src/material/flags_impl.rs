@@ -0,0 +1,48 @@
+use std::ops::{BitOr, BitOrAssign};
+
+use super::BsdfFlags;
+
+impl BsdfFlags {
+    pub const NONE: BsdfFlags = BsdfFlags(0);
+    pub const REFLECTION: BsdfFlags = BsdfFlags(1);
+    pub const TRANSMISSION: BsdfFlags = BsdfFlags(1 << 1);
+    pub const DIFFUSE: BsdfFlags = BsdfFlags(1 << 2);
+    pub const GLOSSY: BsdfFlags = BsdfFlags(1 << 3);
+    // delta distribution, only reachable through `sample`
+    pub const SPECULAR: BsdfFlags = BsdfFlags(1 << 4);
+
+    pub fn contains(&self, other: BsdfFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersects(&self, other: BsdfFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_specular(&self) -> bool {
+        self.contains(BsdfFlags::SPECULAR)
+    }
+
+    // has a lobe `eval` can see, so light sampling is worth it
+    pub fn is_non_specular(&self) -> bool {
+        self.intersects(BsdfFlags::DIFFUSE | BsdfFlags::GLOSSY)
+    }
+}
+
+impl BitOr for BsdfFlags {
+    type Output = BsdfFlags;
+
+    fn bitor(self, rhs: BsdfFlags) -> Self::Output {
+        BsdfFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for BsdfFlags {
+    fn bitor_assign(&mut self, rhs: BsdfFlags) {
+        self.0 |= rhs.0;
+    }
+}
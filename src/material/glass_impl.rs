@@ -1,68 +1,100 @@
-use rand::{prelude::ThreadRng, Rng};
-
 use crate::{
-    data::ScatterInfo,
-    entity::Ray,
+    data::BsdfSample,
+    sampler::Sampler,
     some_math::{reflect, refract, Color, Vector3},
 };
 
-use super::{Glass, Material};
+use super::{facing_normal, schlick, BsdfFlags, Glass, Material};
 
 impl Glass {
     pub fn new(color: Color, eta: f64) -> Self {
         Glass { color, eta }
     }
+
+    // reflection and refraction, the fresnel term as their probabilities.
+    // the refraction has probability 0 under total internal reflection
+    fn lobes(&self, wo: &Vector3, normal: &Vector3) -> [BsdfSample; 2] {
+        let facing = facing_normal(wo, normal);
+        // the stored normal points outwards, so wo on its side means entering
+        let refraction_ratio = if (*wo) * (*normal) > 0.0 {
+            1.0 / self.eta
+        } else {
+            self.eta
+        };
+        let cos_theta = ((*wo) * facing).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let reflectance = if sin_theta * refraction_ratio > 1.0 {
+            // total internal reflection
+            1.0
+        } else {
+            let r0 = (1.0 - self.eta) / (1.0 + self.eta);
+            schlick(r0 * r0, cos_theta)
+        };
+        let ray_dir = (-1.0) * (*wo);
+        return [
+            BsdfSample {
+                wi: reflect(&ray_dir, &facing),
+                weight: self.color,
+                pdf: reflectance,
+                flags: BsdfFlags::SPECULAR | BsdfFlags::REFLECTION,
+            },
+            BsdfSample {
+                wi: refract(&ray_dir, &facing, refraction_ratio),
+                weight: self.color,
+                pdf: 1.0 - reflectance,
+                flags: BsdfFlags::SPECULAR | BsdfFlags::TRANSMISSION,
+            },
+        ];
+    }
 }
 
+// smooth dielectric, picks reflection or refraction by the fresnel term
 impl Material for Glass {
-    fn scatter(&self, ray_in: &Ray, hit_normal: &Vector3, rng: &mut ThreadRng) -> ScatterInfo {
-        let reflection_portion = self.get_fresnel(&ray_in.direction, hit_normal);
-        if rng.gen_range(0.0..1.0) < reflection_portion {
-            let scatter_dir = reflect(&ray_in.direction, hit_normal);
-            return ScatterInfo {
-                scatter_dir,
-                color: self.color,
-                pdf: 1.0,
-            };
-        } else {
-            let refraction_ratio;
-            if ray_in.direction * (*hit_normal) > 0.0 {
-                refraction_ratio = self.eta;
-            } else {
-                refraction_ratio = 1.0 / self.eta;
-            }
-            let cos_theta = ((-1.0) * ray_in.direction * (*hit_normal)).min(1.0);
-            let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
-            let connot_refract = sin_theta * refraction_ratio > 1.0;
-            let scatter_dir;
-            if connot_refract {
-                scatter_dir = reflect(&ray_in.direction, hit_normal);
-            } else {
-                scatter_dir = refract(&ray_in.direction, hit_normal, refraction_ratio);
-            }
-            // let scatter_dir = refract(&ray_in.direction, hit_normal, refraction_ratio);
-            return ScatterInfo {
-                scatter_dir,
-                color: self.color,
-                pdf: 1.0,
-            };
+    fn eval(&self, _wo: &Vector3, _wi: &Vector3, _normal: &Vector3) -> Color {
+        Color::BLACK
+    }
+
+    fn pdf(&self, _wo: &Vector3, _wi: &Vector3, _normal: &Vector3) -> f64 {
+        0.0
+    }
+
+    fn sample(
+        &self,
+        wo: &Vector3,
+        normal: &Vector3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<BsdfSample> {
+        let [reflection, refraction] = self.lobes(wo, normal);
+        // choosing the lobe with probability equal to the fresnel term
+        // cancels it out of the weight
+        if sampler.get_1d() < reflection.pdf {
+            return Some(reflection);
         }
+        return Some(refraction);
     }
 
-    fn emit(&self) -> Color {
-        Color::BLACK
+    fn delta_lobes(&self, wo: &Vector3, normal: &Vector3) -> Vec<BsdfSample> {
+        return self
+            .lobes(wo, normal)
+            .iter()
+            .copied()
+            .filter(|lobe| lobe.pdf > 0.0)
+            .collect();
     }
 
-    fn get_color(&self) -> Color {
+    fn flags(&self) -> BsdfFlags {
+        BsdfFlags::SPECULAR | BsdfFlags::REFLECTION | BsdfFlags::TRANSMISSION
+    }
+
+    fn albedo(&self) -> Color {
         self.color
     }
 
-    fn is_light(&self) -> bool {
-        false
+    fn emit(&self) -> Color {
+        Color::BLACK
     }
 
-    fn get_fresnel(&self, ray_in_dir: &Vector3, hit_normal: &Vector3) -> f64 {
-        let f0 = 0.05;
-        return f0 + (1.0 - f0) * ((*ray_in_dir) * (*hit_normal)).abs().powi(5);
+    fn is_light(&self) -> bool {
+        false
     }
 }
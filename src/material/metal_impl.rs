@@ -1,45 +1,149 @@
-use rand::prelude::ThreadRng;
+use std::f64::consts::PI;
 
 use crate::{
-    data::ScatterInfo,
-    entity::Ray,
+    data::BsdfSample,
+    sampler::Sampler,
     some_math::{reflect, Color, Vector3},
-    systems::transform::generate_unit_vec_sphere,
+    systems::transform::rotate_vec_given_normal,
 };
 
-use super::{Material, Metal};
+use super::{facing_normal, schlick, BsdfFlags, Material, Metal};
+
+// below this the lobe is treated as a perfect mirror
+const MIN_ALPHA: f64 = 1e-4;
 
 impl Metal {
     pub fn new(color: Color, fuzz: f64) -> Self {
         Metal { color, fuzz }
     }
-}
 
-impl Material for Metal {
-    fn scatter(&self, ray_in: &Ray, hit_normal: &Vector3, rng: &mut ThreadRng) -> ScatterInfo {
-        let dir = reflect(&ray_in.direction, hit_normal);
-        let scatter_dir = dir + 0.7 * self.fuzz * generate_unit_vec_sphere(rng);
-        return ScatterInfo {
-            scatter_dir: scatter_dir,
-            color: self.color,
+    // ggx roughness
+    fn alpha(&self) -> f64 {
+        self.fuzz * self.fuzz
+    }
+
+    fn is_mirror(&self) -> bool {
+        self.alpha() < MIN_ALPHA
+    }
+
+    fn mirror_sample(&self, wo: &Vector3, facing: &Vector3) -> BsdfSample {
+        return BsdfSample {
+            wi: reflect(&((-1.0) * (*wo)), facing),
+            weight: self.fresnel((*wo) * (*facing)),
             pdf: 1.0,
+            flags: BsdfFlags::SPECULAR | BsdfFlags::REFLECTION,
         };
     }
 
-    fn emit(&self) -> Color {
-        Color::BLACK
+    // per channel schlick, the color is the reflectance at normal incidence
+    fn fresnel(&self, cos_theta: f64) -> Color {
+        Color::new([
+            schlick(self.color.x(), cos_theta),
+            schlick(self.color.y(), cos_theta),
+            schlick(self.color.z(), cos_theta),
+        ])
+    }
+
+    // trowbridge-reitz normal distribution
+    fn distribution(&self, cos_h: f64) -> f64 {
+        let alpha2 = self.alpha() * self.alpha();
+        let denom = cos_h * cos_h * (alpha2 - 1.0) + 1.0;
+        return alpha2 / (PI * denom * denom);
+    }
+
+    // smith masking for one direction
+    fn masking(&self, cos_v: f64) -> f64 {
+        let alpha2 = self.alpha() * self.alpha();
+        return 2.0 * cos_v / (cos_v + (alpha2 + (1.0 - alpha2) * cos_v * cos_v).sqrt());
     }
+}
 
-    fn get_color(&self) -> Color {
+// conductor, a mirror or a ggx microfacet lobe depending on the fuzz
+impl Material for Metal {
+    fn eval(&self, wo: &Vector3, wi: &Vector3, normal: &Vector3) -> Color {
+        if self.is_mirror() {
+            return Color::BLACK;
+        }
+        let normal = facing_normal(wo, normal);
+        let cos_o = (*wo) * normal;
+        let cos_i = (*wi) * normal;
+        if cos_o <= 0.0 || cos_i <= 0.0 {
+            return Color::BLACK;
+        }
+        let half = (*wo + *wi).normalize();
+        let d = self.distribution(half * normal);
+        let g = self.masking(cos_o) * self.masking(cos_i);
+        return self.fresnel((*wi) * half) * (d * g / (4.0 * cos_o * cos_i));
+    }
+
+    fn pdf(&self, wo: &Vector3, wi: &Vector3, normal: &Vector3) -> f64 {
+        if self.is_mirror() {
+            return 0.0;
+        }
+        let normal = facing_normal(wo, normal);
+        if (*wi) * normal <= 0.0 {
+            return 0.0;
+        }
+        let half = (*wo + *wi).normalize();
+        let cos_h = half * normal;
+        return self.distribution(cos_h) * cos_h / (4.0 * ((*wo) * half).abs());
+    }
+
+    fn sample(
+        &self,
+        wo: &Vector3,
+        normal: &Vector3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<BsdfSample> {
+        let facing = facing_normal(wo, normal);
+        let [a, b] = sampler.get_2d();
+        if self.is_mirror() {
+            return Some(self.mirror_sample(wo, &facing));
+        }
+        // half vector from the distribution, then mirror wo around it
+        let alpha2 = self.alpha() * self.alpha();
+        let cos_theta = ((1.0 - a) / (1.0 + (alpha2 - 1.0) * a)).sqrt();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * b;
+        let local = Vector3::new([sin_theta * phi.cos(), cos_theta, sin_theta * phi.sin()]);
+        let half = rotate_vec_given_normal(&local, &facing);
+        let wi = reflect(&((-1.0) * (*wo)), &half);
+        let pdf = self.pdf(wo, &wi, normal);
+        if pdf <= 0.0 {
+            return None;
+        }
+        let weight = self.eval(wo, &wi, normal) * ((wi * facing) / pdf);
+        return Some(BsdfSample {
+            wi,
+            weight,
+            pdf,
+            flags: BsdfFlags::GLOSSY | BsdfFlags::REFLECTION,
+        });
+    }
+
+    fn delta_lobes(&self, wo: &Vector3, normal: &Vector3) -> Vec<BsdfSample> {
+        if self.is_mirror() {
+            return vec![self.mirror_sample(wo, &facing_normal(wo, normal))];
+        }
+        return Vec::new();
+    }
+
+    fn flags(&self) -> BsdfFlags {
+        if self.is_mirror() {
+            return BsdfFlags::SPECULAR | BsdfFlags::REFLECTION;
+        }
+        return BsdfFlags::GLOSSY | BsdfFlags::REFLECTION;
+    }
+
+    fn albedo(&self) -> Color {
         self.color
     }
 
-    fn is_light(&self) -> bool {
-        false
+    fn emit(&self) -> Color {
+        Color::BLACK
     }
 
-    fn get_fresnel(&self, ray_in_dir: &Vector3, hit_normal: &Vector3) -> f64 {
-        let f0 = 0.9;
-        return f0 + (1.0 - f0) * (1.0 - ((*ray_in_dir) * (*hit_normal)).abs()).powi(5);
+    fn is_light(&self) -> bool {
+        false
     }
 }
@@ -0,0 +1,81 @@
+mod blue_noise_impl;
+mod halton_impl;
+mod hash_impl;
+mod kind_impl;
+mod pixel_rng_impl;
+mod random_impl;
+mod sobol_impl;
+mod stratified_impl;
+
+// sample source used by camera, lights and materials. every get_1d / get_2d
+// call moves on to the next dimension, so the pixel, light and bsdf samples
+// of one path draw from separate, decorrelated dimensions of the sequence
+pub trait Sampler {
+    // restart at dimension 0 for one sample of one pixel
+    fn start_sample(&mut self, col: u32, row: u32, sample: usize);
+    fn get_1d(&mut self) -> f64;
+    fn get_2d(&mut self) -> [f64; 2];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplerKind {
+    Random,
+    Stratified,
+    Halton,
+    Sobol,
+    BlueNoise,
+}
+
+// small counter-seeded generator, one stream per (seed, pixel, sample) so the
+// image does not depend on which thread renders which pixel
+#[derive(Debug, Clone)]
+pub struct PixelRng {
+    state: u64,
+    inc: u64,
+}
+
+// independent uniform numbers
+#[derive(Debug, Clone)]
+pub struct RandomSampler {
+    seed: u64,
+    rng: PixelRng,
+}
+
+// jittered strata, shuffled independently in every dimension
+#[derive(Debug, Clone)]
+pub struct StratifiedSampler {
+    seed: u64,
+    samples_per_pixel: usize,
+    pixel_key: u64,
+    sample: usize,
+    dimension: usize,
+}
+
+// radical inverse in prime bases with per-pixel random digit scrambling
+#[derive(Debug, Clone)]
+pub struct HaltonSampler {
+    seed: u64,
+    pixel_key: u64,
+    sample: usize,
+    dimension: usize,
+}
+
+// 2d owen-scrambled sobol points, padded with a new scramble per dimension
+#[derive(Debug, Clone)]
+pub struct SobolSampler {
+    seed: u64,
+    pixel_key: u64,
+    sample: usize,
+    dimension: usize,
+}
+
+// one sobol sequence shared by all pixels, shifted per pixel by a blue noise
+// mask so that the remaining error is spread as high frequency noise
+#[derive(Debug, Clone)]
+pub struct BlueNoiseSampler {
+    seed: u64,
+    col: u32,
+    row: u32,
+    sample: usize,
+    dimension: usize,
+}
@@ -0,0 +1,154 @@
+use std::sync::OnceLock;
+
+use rand::Rng;
+
+use super::{
+    hash_impl::hash_combine, sobol_impl::owen_sobol_point, BlueNoiseSampler, PixelRng, Sampler,
+};
+
+const MASK_SIZE: usize = 64;
+// gaussian of the void-and-cluster energy, truncated at MASK_RADIUS pixels
+const MASK_SIGMA: f64 = 1.5;
+const MASK_RADIUS: i32 = 6;
+
+static MASK: OnceLock<Vec<f64>> = OnceLock::new();
+
+impl BlueNoiseSampler {
+    pub fn new(seed: u64) -> Self {
+        // build the mask up front instead of inside the first worker
+        blue_noise_mask();
+        BlueNoiseSampler {
+            seed,
+            col: 0,
+            row: 0,
+            sample: 0,
+            dimension: 0,
+        }
+    }
+
+    fn mask_value(&self, offset: u64) -> f64 {
+        let col = (self.col as usize + (offset & 63) as usize) % MASK_SIZE;
+        let row = (self.row as usize + ((offset >> 6) & 63) as usize) % MASK_SIZE;
+        return blue_noise_mask()[row * MASK_SIZE + col];
+    }
+}
+
+impl Sampler for BlueNoiseSampler {
+    fn start_sample(&mut self, col: u32, row: u32, sample: usize) {
+        self.col = col;
+        self.row = row;
+        self.sample = sample;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        self.get_2d()[0]
+    }
+
+    fn get_2d(&mut self) -> [f64; 2] {
+        // same point in every pixel, toroidal shift read from two places of the mask
+        let key = hash_combine(self.seed, self.dimension as u64);
+        self.dimension += 1;
+        let point = owen_sobol_point(self.sample as u32, key);
+        let shift = [self.mask_value(key >> 12), self.mask_value(key >> 24)];
+        let mut res = [0.0; 2];
+        for n in 0..2 {
+            res[n] = (point[n] + shift[n]).fract();
+        }
+        return res;
+    }
+}
+
+fn blue_noise_mask() -> &'static [f64] {
+    MASK.get_or_init(build_mask)
+}
+
+// void-and-cluster, Ulichney 1993. ranks every pixel of a tileable mask and
+// maps the rank to (0, 1)
+fn build_mask() -> Vec<f64> {
+    let len = MASK_SIZE * MASK_SIZE;
+    let mut pattern = vec![false; len];
+    let mut energy = vec![0.0; len];
+    let mut rng = PixelRng::new(0, 0, 0);
+    let mut ones = 0;
+    while ones < len / 10 {
+        let index = rng.gen_range(0..len);
+        if !pattern[index] {
+            pattern[index] = true;
+            splat(&mut energy, index, 1.0);
+            ones += 1;
+        }
+    }
+    // move the tightest cluster into the largest void until the pattern settles
+    for _ in 0..len {
+        let cluster = tightest_cluster(&pattern, &energy);
+        pattern[cluster] = false;
+        splat(&mut energy, cluster, -1.0);
+        let void = largest_void(&pattern, &energy);
+        pattern[void] = true;
+        splat(&mut energy, void, 1.0);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; len];
+    // ranks below the initial pattern, by removing clusters
+    let (mut removed, mut removed_energy) = (pattern.clone(), energy.clone());
+    for rank in (0..ones).rev() {
+        let cluster = tightest_cluster(&removed, &removed_energy);
+        removed[cluster] = false;
+        splat(&mut removed_energy, cluster, -1.0);
+        ranks[cluster] = rank;
+    }
+    // ranks above it, by filling voids
+    for rank in ones..len {
+        let void = largest_void(&pattern, &energy);
+        pattern[void] = true;
+        splat(&mut energy, void, 1.0);
+        ranks[void] = rank;
+    }
+    return ranks
+        .iter()
+        .map(|rank| (*rank as f64 + 0.5) / len as f64)
+        .collect();
+}
+
+fn splat(energy: &mut [f64], index: usize, weight: f64) {
+    let size = MASK_SIZE as i32;
+    let col = (index % MASK_SIZE) as i32;
+    let row = (index / MASK_SIZE) as i32;
+    for dy in -MASK_RADIUS..=MASK_RADIUS {
+        for dx in -MASK_RADIUS..=MASK_RADIUS {
+            let r_square = (dx * dx + dy * dy) as f64;
+            let target_col = (col + dx).rem_euclid(size) as usize;
+            let target_row = (row + dy).rem_euclid(size) as usize;
+            energy[target_row * MASK_SIZE + target_col] +=
+                weight * (-r_square / (2.0 * MASK_SIGMA * MASK_SIGMA)).exp();
+        }
+    }
+}
+
+fn tightest_cluster(pattern: &[bool], energy: &[f64]) -> usize {
+    let mut res = 0;
+    let mut max = f64::NEG_INFINITY;
+    for (index, value) in energy.iter().enumerate() {
+        if pattern[index] && *value > max {
+            max = *value;
+            res = index;
+        }
+    }
+    return res;
+}
+
+fn largest_void(pattern: &[bool], energy: &[f64]) -> usize {
+    let mut res = 0;
+    let mut min = f64::INFINITY;
+    for (index, value) in energy.iter().enumerate() {
+        if !pattern[index] && *value < min {
+            min = *value;
+            res = index;
+        }
+    }
+    return res;
+}
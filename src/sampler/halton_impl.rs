@@ -0,0 +1,66 @@
+use super::{
+    hash_impl::{hash_combine, permute, pixel_key, to_unit},
+    HaltonSampler, Sampler,
+};
+
+// one base per dimension, later dimensions fall back to hashed random numbers
+const PRIMES: [u32; 48] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223,
+];
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        HaltonSampler {
+            seed,
+            pixel_key: 0,
+            sample: 0,
+            dimension: 0,
+        }
+    }
+
+    fn next_value(&mut self) -> f64 {
+        let key = hash_combine(self.pixel_key, self.dimension as u64);
+        let dimension = self.dimension;
+        self.dimension += 1;
+        if dimension >= PRIMES.len() {
+            return to_unit(hash_combine(key, self.sample as u64));
+        }
+        return scrambled_radical_inverse(self.sample as u64, PRIMES[dimension], key);
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_sample(&mut self, col: u32, row: u32, sample: usize) {
+        self.pixel_key = pixel_key(self.seed, col, row);
+        self.sample = sample;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        self.next_value()
+    }
+
+    fn get_2d(&mut self) -> [f64; 2] {
+        [self.next_value(), self.next_value()]
+    }
+}
+
+// every digit goes through its own permutation of 0..base, trailing zero
+// digits included so the value is scrambled down to double precision
+fn scrambled_radical_inverse(mut index: u64, base: u32, key: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut inv_base_n = 1.0;
+    let mut res = 0.0;
+    let mut digit_num = 0u64;
+    while inv_base_n * inv_base > f64::EPSILON {
+        let digit = (index % base as u64) as u32;
+        index /= base as u64;
+        inv_base_n *= inv_base;
+        let digit_key = hash_combine(key, digit_num) as u32;
+        res += permute(digit, base, digit_key) as f64 * inv_base_n;
+        digit_num += 1;
+    }
+    return res.min(1.0 - f64::EPSILON / 2.0);
+}
@@ -0,0 +1,41 @@
+use super::{
+    BlueNoiseSampler, HaltonSampler, RandomSampler, Sampler, SamplerKind, SobolSampler,
+    StratifiedSampler,
+};
+
+impl SamplerKind {
+    pub fn build(self, seed: u64, samples_per_pixel: usize) -> Box<dyn Sampler + Send> {
+        match self {
+            SamplerKind::Random => Box::new(RandomSampler::new(seed)),
+            SamplerKind::Stratified => Box::new(StratifiedSampler::new(seed, samples_per_pixel)),
+            SamplerKind::Halton => Box::new(HaltonSampler::new(seed)),
+            SamplerKind::Sobol => Box::new(SobolSampler::new(seed)),
+            SamplerKind::BlueNoise => Box::new(BlueNoiseSampler::new(seed)),
+        }
+    }
+
+    pub const ALL: [SamplerKind; 5] = [
+        SamplerKind::Random,
+        SamplerKind::Stratified,
+        SamplerKind::Halton,
+        SamplerKind::Sobol,
+        SamplerKind::BlueNoise,
+    ];
+
+    pub fn from_label(label: &str) -> Option<SamplerKind> {
+        return SamplerKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.label() == label);
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SamplerKind::Random => "random",
+            SamplerKind::Stratified => "stratified",
+            SamplerKind::Halton => "halton",
+            SamplerKind::Sobol => "sobol",
+            SamplerKind::BlueNoise => "blue-noise",
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use rand::{Error, RngCore};
+
+use super::{hash_impl::splitmix64, PixelRng};
+
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+
+impl PixelRng {
+    pub fn new(seed: u64, pixel: u64, sample: u64) -> Self {
+        // hash the three keys into a start state and a stream selector
+        let key = splitmix64(splitmix64(seed ^ splitmix64(pixel)) ^ sample);
+        let mut rng = PixelRng {
+            state: 0,
+            inc: (splitmix64(key) << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(key);
+        rng.step();
+        return rng;
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(PCG_MULTIPLIER)
+            .wrapping_add(self.inc);
+    }
+}
+
+impl RngCore for PixelRng {
+    // pcg32, xsh-rr output
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        return xorshifted.rotate_right(rot);
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+        let low = self.next_u32() as u64;
+        return (high << 32) | low;
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        return Ok(());
+    }
+}
@@ -0,0 +1,26 @@
+use rand::Rng;
+
+use super::{hash_impl::pixel_key, PixelRng, RandomSampler, Sampler};
+
+impl RandomSampler {
+    pub fn new(seed: u64) -> Self {
+        RandomSampler {
+            seed,
+            rng: PixelRng::new(seed, 0, 0),
+        }
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn start_sample(&mut self, col: u32, row: u32, sample: usize) {
+        self.rng = PixelRng::new(self.seed, pixel_key(0, col, row), sample as u64);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        self.rng.gen_range(0.0..1.0)
+    }
+
+    fn get_2d(&mut self) -> [f64; 2] {
+        [self.rng.gen_range(0.0..1.0), self.rng.gen_range(0.0..1.0)]
+    }
+}
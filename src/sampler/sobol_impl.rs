@@ -0,0 +1,92 @@
+use super::{
+    hash_impl::{hash_combine, pixel_key, to_unit_u32},
+    Sampler, SobolSampler,
+};
+
+// generator matrices of the first two sobol dimensions,
+// primitive polynomial x + 1 for the second one (Joe & Kuo)
+const SOBOL_DIRECTIONS: [[u32; 32]; 2] = sobol_directions();
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> Self {
+        SobolSampler {
+            seed,
+            pixel_key: 0,
+            sample: 0,
+            dimension: 0,
+        }
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_sample(&mut self, col: u32, row: u32, sample: usize) {
+        self.pixel_key = pixel_key(self.seed, col, row);
+        self.sample = sample;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let key = hash_combine(self.pixel_key, self.dimension as u64);
+        self.dimension += 1;
+        return owen_sobol_point(self.sample as u32, key)[0];
+    }
+
+    fn get_2d(&mut self) -> [f64; 2] {
+        let key = hash_combine(self.pixel_key, self.dimension as u64);
+        self.dimension += 1;
+        return owen_sobol_point(self.sample as u32, key);
+    }
+}
+
+// Burley 2020, "Practical Hash-based Owen Scrambling": the index is shuffled
+// and both coordinates are scrambled with seeds derived from `key`
+pub fn owen_sobol_point(index: u32, key: u64) -> [f64; 2] {
+    let index = nested_uniform_scramble(index, key as u32);
+    let mut res = [0.0; 2];
+    for (dimension, value) in res.iter_mut().enumerate() {
+        let seed = hash_combine(key, dimension as u64 + 1) as u32;
+        *value = to_unit_u32(nested_uniform_scramble(sobol(index, dimension), seed));
+    }
+    return res;
+}
+
+fn sobol(mut index: u32, dimension: usize) -> u32 {
+    let mut res = 0;
+    let mut bit = 0;
+    while index != 0 {
+        if index & 1 == 1 {
+            res ^= SOBOL_DIRECTIONS[dimension][bit];
+        }
+        index >>= 1;
+        bit += 1;
+    }
+    return res;
+}
+
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    return x;
+}
+
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+const fn sobol_directions() -> [[u32; 32]; 2] {
+    let mut res = [[0u32; 32]; 2];
+    res[1][0] = 1 << 31;
+    let mut k = 0;
+    while k < 32 {
+        // first dimension is the van der corput sequence
+        res[0][k] = 1 << (31 - k);
+        if k > 0 {
+            res[1][k] = res[1][k - 1] ^ (res[1][k - 1] >> 1);
+        }
+        k += 1;
+    }
+    return res;
+}
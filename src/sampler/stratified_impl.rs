@@ -0,0 +1,48 @@
+use super::{
+    hash_impl::{hash_combine, permute, pixel_key, to_unit},
+    Sampler, StratifiedSampler,
+};
+
+impl StratifiedSampler {
+    pub fn new(seed: u64, samples_per_pixel: usize) -> Self {
+        StratifiedSampler {
+            seed,
+            samples_per_pixel: samples_per_pixel.max(1),
+            pixel_key: 0,
+            sample: 0,
+            dimension: 0,
+        }
+    }
+
+    // (stratum of this sample, hash for the jitter) in the current dimension
+    fn next_stratum(&mut self, strata: usize) -> (usize, u64) {
+        let key = hash_combine(self.pixel_key, self.dimension as u64);
+        self.dimension += 1;
+        let stratum = permute((self.sample % strata) as u32, strata as u32, key as u32);
+        return (stratum as usize, hash_combine(key, self.sample as u64));
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_sample(&mut self, col: u32, row: u32, sample: usize) {
+        self.pixel_key = pixel_key(self.seed, col, row);
+        self.sample = sample;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let strata = self.samples_per_pixel;
+        let (stratum, jitter) = self.next_stratum(strata);
+        return (stratum as f64 + to_unit(jitter)) / strata as f64;
+    }
+
+    fn get_2d(&mut self) -> [f64; 2] {
+        // smallest grid with at least one cell per sample
+        let x_num = (self.samples_per_pixel as f64).sqrt() as usize;
+        let y_num = self.samples_per_pixel.div_ceil(x_num);
+        let (stratum, jitter) = self.next_stratum(x_num * y_num);
+        let x = (stratum % x_num) as f64 + to_unit(jitter);
+        let y = (stratum / x_num) as f64 + to_unit(hash_combine(jitter, 1));
+        return [x / x_num as f64, y / y_num as f64];
+    }
+}
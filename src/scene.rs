@@ -0,0 +1,121 @@
+use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::some_math::Vector3;
+
+mod obj_impl;
+mod parse_impl;
+
+pub use obj_impl::load_obj;
+
+// scene file layout, mirrors the constructors of camera / material / entity
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneDesc {
+    pub camera: CameraDesc,
+    #[serde(default)]
+    pub materials: BTreeMap<String, MaterialDesc>,
+    #[serde(default)]
+    pub objects: Vec<ObjectDesc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraDesc {
+    pub position: [f64; 3],
+    pub lookat: [f64; 3],
+    pub updir: [f64; 3],
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MaterialDesc {
+    Diffuse { color: [f64; 3] },
+    Metal { color: [f64; 3], fuzz: f64 },
+    Glass { color: [f64; 3], eta: f64 },
+    DiffuseLight { color: [f64; 3] },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ObjectDesc {
+    Panel {
+        points: [[f64; 3]; 2],
+        normal: [f64; 3],
+        material: String,
+    },
+    Rectangle {
+        points: [[f64; 3]; 2],
+        // angle in radians
+        angle_rotate_y: Option<f64>,
+        material: String,
+    },
+    Sphere {
+        center: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Triangle {
+        vertices: [[f64; 3]; 3],
+        normals: Option<[[f64; 3]; 3]>,
+        material: String,
+    },
+    // wavefront obj file, materials come from its mtl library unless overridden
+    Mesh {
+        path: PathBuf,
+        material: Option<String>,
+        scale: Option<f64>,
+        // angle in radians
+        angle_rotate_y: Option<f64>,
+        translate: Option<[f64; 3]>,
+    },
+}
+
+// placement of an imported mesh: scale, then rotate around y, then translate
+#[derive(Debug, Clone, Copy)]
+pub struct MeshTransform {
+    pub scale: f64,
+    pub angle_rotate_y: Option<f64>,
+    pub translate: Vector3,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Parse {
+        line: usize,
+        col: usize,
+        message: String,
+    },
+    Mesh {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneError::Io(err) => write!(f, "cannot read scene file: {}", err),
+            SceneError::Parse { line, col, message } => {
+                write!(
+                    f,
+                    "scene error at line {}, column {}: {}",
+                    line, col, message
+                )
+            }
+            SceneError::Mesh { path, message } => {
+                write!(f, "cannot load mesh {}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<std::io::Error> for SceneError {
+    fn from(err: std::io::Error) -> Self {
+        SceneError::Io(err)
+    }
+}
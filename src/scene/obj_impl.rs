@@ -0,0 +1,178 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    entity::Triangle,
+    material::{DiffuseLight, DiffuseMat, Glass, Material, Metal},
+    some_math::{Color, Point, Vector3},
+    systems::transform::rotate_around_y,
+};
+
+use super::{MeshTransform, SceneError};
+
+const DEFAULT_COLOR: [f64; 3] = [0.75, 0.75, 0.75];
+
+impl MeshTransform {
+    pub fn apply_point(&self, point: Point) -> Point {
+        let scaled = self.scale * point;
+        return match self.angle_rotate_y {
+            Some(angle) => rotate_around_y(scaled, angle),
+            None => scaled,
+        } + self.translate;
+    }
+
+    pub fn apply_normal(&self, normal: Vector3) -> Vector3 {
+        return match self.angle_rotate_y {
+            Some(angle) => rotate_around_y(normal, angle),
+            None => normal,
+        }
+        .normalize();
+    }
+}
+
+impl Default for MeshTransform {
+    fn default() -> Self {
+        MeshTransform {
+            scale: 1.0,
+            angle_rotate_y: None,
+            translate: Vector3::default(),
+        }
+    }
+}
+
+// every obj group / object becomes its own object id starting at `first_id`,
+// so the denoiser treats a smooth mesh part as one surface
+pub fn load_obj<P: AsRef<Path>>(
+    path: P,
+    transform: &MeshTransform,
+    material_override: Option<Arc<dyn Material + Send + Sync>>,
+    first_id: usize,
+) -> Result<Vec<Arc<Triangle>>, SceneError> {
+    let path = path.as_ref();
+    let mesh_error = |message: String| SceneError::Mesh {
+        path: path.to_path_buf(),
+        message,
+    };
+    if transform.scale <= 0.0 || transform.scale.is_nan() {
+        return Err(mesh_error(format!(
+            "scale must be positive, got {}",
+            transform.scale
+        )));
+    }
+    let options = tobj::LoadOptions {
+        single_index: true,
+        triangulate: true,
+        ignore_points: true,
+        ignore_lines: true,
+    };
+    let (models, mtl_result) =
+        tobj::load_obj(path, &options).map_err(|err| mesh_error(err.to_string()))?;
+    let materials: Vec<Arc<dyn Material + Send + Sync>> = match material_override {
+        Some(_) => Vec::new(),
+        None => mtl_result
+            .map_err(|err| mesh_error(format!("mtl library: {}", err)))?
+            .iter()
+            .map(convert_material)
+            .collect(),
+    };
+    let default_material: Arc<dyn Material + Send + Sync> = match material_override {
+        Some(material) => material,
+        None => Arc::new(DiffuseMat::new(Color::new(DEFAULT_COLOR))),
+    };
+
+    let mut res = Vec::new();
+    let mut id = first_id;
+    for model in models.iter() {
+        let model_start = res.len();
+        let mesh = &model.mesh;
+        if mesh.indices.is_empty() {
+            continue;
+        }
+        let material = match mesh.material_id.and_then(|n| materials.get(n)) {
+            Some(material) => material.clone(),
+            None => default_material.clone(),
+        };
+        for face in mesh.indices.chunks_exact(3) {
+            let mut vertices = [Point::default(); 3];
+            let mut normals = [Vector3::default(); 3];
+            let mut texcoords = [[0.0; 2]; 3];
+            for (n, index) in face.iter().enumerate() {
+                let index = *index as usize;
+                vertices[n] = transform.apply_point(Point::new([
+                    mesh.positions[3 * index],
+                    mesh.positions[3 * index + 1],
+                    mesh.positions[3 * index + 2],
+                ]));
+                if !mesh.normals.is_empty() {
+                    normals[n] = transform.apply_normal(Vector3::new([
+                        mesh.normals[3 * index],
+                        mesh.normals[3 * index + 1],
+                        mesh.normals[3 * index + 2],
+                    ]));
+                }
+                if !mesh.texcoords.is_empty() {
+                    texcoords[n] = [mesh.texcoords[2 * index], mesh.texcoords[2 * index + 1]];
+                }
+            }
+            let triangle = Triangle::new(
+                vertices,
+                if mesh.normals.is_empty() {
+                    None
+                } else {
+                    Some(normals)
+                },
+                if mesh.texcoords.is_empty() {
+                    None
+                } else {
+                    Some(texcoords)
+                },
+                material.clone(),
+                id,
+            );
+            // skip degenerate faces
+            if triangle.area() > 0.0 {
+                res.push(Arc::new(triangle));
+            }
+        }
+        // ids stay below first_id + res.len(), where the caller continues
+        if res.len() > model_start {
+            id += 1;
+        }
+    }
+    return Ok(res);
+}
+
+fn convert_material(mtl: &tobj::Material) -> Arc<dyn Material + Send + Sync> {
+    let diffuse = Color::new(mtl.diffuse.unwrap_or(DEFAULT_COLOR));
+    if let Some(emission) = mtl.unknown_param.get("Ke").and_then(|ke| parse_color(ke)) {
+        if emission.length_square() > 0.0 {
+            return Arc::new(DiffuseLight::new(emission));
+        }
+    }
+    let illumination = mtl.illumination_model.unwrap_or(2);
+    let transparent =
+        mtl.dissolve.is_some_and(|d| d < 1.0) || matches!(illumination, 4 | 6 | 7 | 9);
+    if transparent {
+        let color = mtl
+            .unknown_param
+            .get("Tf")
+            .and_then(|tf| parse_color(tf))
+            .unwrap_or(diffuse);
+        return Arc::new(Glass::new(color, mtl.optical_density.unwrap_or(1.5)));
+    }
+    if matches!(illumination, 3 | 5 | 8) {
+        let color = mtl.specular.map(Color::new).unwrap_or(diffuse);
+        // Ns runs from 0 (rough) to 1000 (mirror)
+        let fuzz = 1.0 - (mtl.shininess.unwrap_or(1000.0) / 1000.0).clamp(0.0, 1.0);
+        return Arc::new(Metal::new(color, fuzz));
+    }
+    return Arc::new(DiffuseMat::new(diffuse));
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let mut data = [0.0; 3];
+    let mut words = value.split_whitespace();
+    for num in data.iter_mut() {
+        *num = words.next()?.parse().ok()?;
+    }
+    return Some(Color::new(data));
+}
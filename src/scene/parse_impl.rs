@@ -0,0 +1,171 @@
+use std::{collections::BTreeMap, fs, ops::Range, path::Path};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use super::{MaterialDesc, ObjectDesc, SceneDesc, SceneError};
+
+// second look at the file, only to locate the bad fields
+#[derive(Deserialize)]
+struct SceneSpans {
+    #[serde(default)]
+    materials: BTreeMap<String, MaterialSpan>,
+    #[serde(default)]
+    objects: Vec<ObjectSpan>,
+}
+
+#[derive(Deserialize)]
+struct MaterialSpan {
+    eta: Option<Spanned<f64>>,
+}
+
+#[derive(Deserialize)]
+struct ObjectSpan {
+    material: Option<Spanned<String>>,
+    normal: Option<Spanned<[f64; 3]>>,
+    radius: Option<Spanned<f64>>,
+}
+
+// a value that deserializes fine but can not be rendered, by object index
+// or material name
+enum BadField<'a> {
+    Material(usize),
+    Normal(usize),
+    Radius(usize),
+    Eta(&'a str),
+}
+
+impl SceneDesc {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SceneError> {
+        let source = fs::read_to_string(path.as_ref())?;
+        let mut scene = SceneDesc::from_source(&source)?;
+        // mesh files are relative to the scene file
+        if let Some(dir) = path.as_ref().parent() {
+            for object in scene.objects.iter_mut() {
+                if let ObjectDesc::Mesh { path, .. } = object {
+                    if path.is_relative() {
+                        *path = dir.join(&path);
+                    }
+                }
+            }
+        }
+        return Ok(scene);
+    }
+
+    pub fn from_source(source: &str) -> Result<Self, SceneError> {
+        let scene: SceneDesc = toml::from_str(source).map_err(|err| {
+            parse_error(
+                source,
+                err.span().unwrap_or(0..0),
+                err.message().to_string(),
+            )
+        })?;
+        let bad_fields = scene.bad_fields();
+        if bad_fields.is_empty() {
+            return Ok(scene);
+        }
+        let spans: SceneSpans = toml::from_str(source).map_err(|err| {
+            parse_error(
+                source,
+                err.span().unwrap_or(0..0),
+                err.message().to_string(),
+            )
+        })?;
+        // report whichever problem comes first in the file
+        let (span, message) = bad_fields
+            .into_iter()
+            .map(|(field, message)| {
+                let span = match field {
+                    BadField::Material(index) => {
+                        spans.objects[index].material.as_ref().map(Spanned::span)
+                    }
+                    BadField::Normal(index) => {
+                        spans.objects[index].normal.as_ref().map(Spanned::span)
+                    }
+                    BadField::Radius(index) => {
+                        spans.objects[index].radius.as_ref().map(Spanned::span)
+                    }
+                    BadField::Eta(name) => spans.materials[name].eta.as_ref().map(Spanned::span),
+                };
+                (span.unwrap(), message)
+            })
+            .min_by_key(|(span, _)| span.start)
+            .unwrap();
+        return Err(parse_error(source, span, message));
+    }
+
+    fn bad_fields(&self) -> Vec<(BadField<'_>, String)> {
+        let mut res = Vec::new();
+        for (name, material) in self.materials.iter() {
+            if let MaterialDesc::Glass { eta, .. } = *material {
+                if !is_positive(eta) {
+                    res.push((
+                        BadField::Eta(name),
+                        format!("glass eta {} is not positive", eta),
+                    ));
+                }
+            }
+        }
+        for (index, object) in self.objects.iter().enumerate() {
+            if let Some(name) = object.material_name() {
+                if !self.materials.contains_key(name) {
+                    res.push((
+                        BadField::Material(index),
+                        format!("unknown material `{}`", name),
+                    ));
+                }
+            }
+            match *object {
+                ObjectDesc::Panel { normal, .. } if !is_axis_normal(&normal) => res.push((
+                    BadField::Normal(index),
+                    format!(
+                        "panel normal {:?} is not a non-zero axis-aligned vector",
+                        normal
+                    ),
+                )),
+                ObjectDesc::Sphere { radius, .. } if !is_positive(radius) => res.push((
+                    BadField::Radius(index),
+                    format!("sphere radius {} is not positive", radius),
+                )),
+                _ => {}
+            }
+        }
+        return res;
+    }
+}
+
+fn is_positive(value: f64) -> bool {
+    value > 0.0 && value.is_finite()
+}
+
+// panels lie in an axis plane, so exactly one component may be non-zero
+fn is_axis_normal(normal: &[f64; 3]) -> bool {
+    if normal.iter().any(|value| !value.is_finite()) {
+        return false;
+    }
+    return normal.iter().filter(|value| **value != 0.0).count() == 1;
+}
+
+impl ObjectDesc {
+    pub fn material_name(&self) -> Option<&str> {
+        match self {
+            ObjectDesc::Panel { material, .. } => Some(material),
+            ObjectDesc::Rectangle { material, .. } => Some(material),
+            ObjectDesc::Sphere { material, .. } => Some(material),
+            ObjectDesc::Triangle { material, .. } => Some(material),
+            ObjectDesc::Mesh { material, .. } => material.as_deref(),
+        }
+    }
+}
+
+fn parse_error(source: &str, span: Range<usize>, message: String) -> SceneError {
+    // 1-based line / column of the first byte of the span
+    let offset = span.start.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let col = match before.rfind('\n') {
+        Some(pos) => before[pos + 1..].chars().count() + 1,
+        None => before.chars().count() + 1,
+    };
+    SceneError::Parse { line, col, message }
+}
@@ -0,0 +1,37 @@
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+
+mod scheduler_impl;
+mod stats_impl;
+
+// runs a batch of independent jobs on scoped threads. jobs are dealt round
+// robin into one queue per thread, a thread that runs dry steals from the back
+// of the other queues
+pub struct Scheduler {
+    thread_num: usize,
+    cancel: CancelToken,
+}
+
+// shared flag, once set no new job is started and `run` returns what is done
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    flag: Arc<AtomicBool>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorkerStats {
+    pub jobs: usize,
+    // jobs taken from another thread's queue
+    pub stolen: usize,
+    // time spent inside jobs
+    pub busy: Duration,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchedulerStats {
+    pub wall_time: Duration,
+    pub workers: Vec<WorkerStats>,
+    pub cancelled: bool,
+}
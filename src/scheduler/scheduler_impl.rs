@@ -0,0 +1,126 @@
+use std::{
+    collections::VecDeque,
+    panic,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+    time::Instant,
+};
+
+use super::{CancelToken, Scheduler, SchedulerStats, WorkerStats};
+
+impl Scheduler {
+    pub fn new(thread_num: usize, cancel: CancelToken) -> Self {
+        Scheduler {
+            thread_num: thread_num.max(1),
+            cancel,
+        }
+    }
+
+    pub fn thread_num(&self) -> usize {
+        self.thread_num
+    }
+
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    // `init` builds the per thread state, `job_fn` runs one job with it and
+    // `on_done(done, total)` is called after each job. results come back in job
+    // order, None for jobs skipped after cancellation
+    pub fn run<J, R, S, I, F, P>(
+        &self,
+        jobs: Vec<J>,
+        init: I,
+        job_fn: F,
+        on_done: P,
+    ) -> (Vec<Option<R>>, SchedulerStats)
+    where
+        J: Send,
+        R: Send,
+        I: Fn(usize) -> S + Sync,
+        F: Fn(&mut S, J) -> R + Sync,
+        P: Fn(usize, usize) + Sync,
+    {
+        let start = Instant::now();
+        let total = jobs.len();
+        let thread_num = self.thread_num.min(total).max(1);
+        let queues: Vec<Mutex<VecDeque<(usize, J)>>> = (0..thread_num)
+            .map(|_| Mutex::new(VecDeque::with_capacity(total / thread_num + 1)))
+            .collect();
+        for (index, job) in jobs.into_iter().enumerate() {
+            lock(&queues[index % thread_num]).push_back((index, job));
+        }
+        let done = AtomicUsize::new(0);
+
+        let mut results: Vec<Option<R>> = (0..total).map(|_| None).collect();
+        let mut workers = Vec::with_capacity(thread_num);
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..thread_num)
+                .map(|id| {
+                    let (queues, done) = (&queues, &done);
+                    let (init, job_fn, on_done) = (&init, &job_fn, &on_done);
+                    scope.spawn(move || {
+                        let mut state = init(id);
+                        let mut stats = WorkerStats::default();
+                        let mut res = Vec::new();
+                        while !self.cancel.is_cancelled() {
+                            let (index, job, stolen) = match next_job(queues, id) {
+                                Some(next) => next,
+                                None => break,
+                            };
+                            let job_start = Instant::now();
+                            res.push((index, job_fn(&mut state, job)));
+                            stats.busy += job_start.elapsed();
+                            stats.jobs += 1;
+                            if stolen {
+                                stats.stolen += 1;
+                            }
+                            on_done(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+                        }
+                        (res, stats)
+                    })
+                })
+                .collect();
+            for handle in handles {
+                match handle.join() {
+                    Ok((res, stats)) => {
+                        for (index, value) in res {
+                            results[index] = Some(value);
+                        }
+                        workers.push(stats);
+                    }
+                    // a job panicked, hand the panic to the caller
+                    Err(err) => panic::resume_unwind(err),
+                }
+            }
+        });
+        let stats = SchedulerStats {
+            wall_time: start.elapsed(),
+            workers,
+            cancelled: done.load(Ordering::Relaxed) < total,
+        };
+        return (results, stats);
+    }
+}
+
+// own queue from the front, otherwise steal from the back of the others
+fn next_job<J>(queues: &[Mutex<VecDeque<(usize, J)>>], id: usize) -> Option<(usize, J, bool)> {
+    if let Some((index, job)) = lock(&queues[id]).pop_front() {
+        return Some((index, job, false));
+    }
+    for offset in 1..queues.len() {
+        let victim = (id + offset) % queues.len();
+        if let Some((index, job)) = lock(&queues[victim]).pop_back() {
+            return Some((index, job, true));
+        }
+    }
+    return None;
+}
+
+// jobs never run while a queue is locked, so a poisoned queue is still consistent
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,66 @@
+use std::{fmt::Display, sync::atomic::Ordering, time::Duration};
+
+use super::{CancelToken, SchedulerStats};
+
+impl CancelToken {
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+
+    // clear the flag so the token can be used for another run
+    pub fn reset(&self) {
+        self.flag.store(false, Ordering::Relaxed);
+    }
+}
+
+impl SchedulerStats {
+    pub fn jobs(&self) -> usize {
+        self.workers.iter().map(|worker| worker.jobs).sum()
+    }
+
+    // busy time over wall time, averaged over the threads
+    pub fn utilization(&self) -> f64 {
+        if self.workers.is_empty() || self.wall_time == Duration::ZERO {
+            return 0.0;
+        }
+        let busy: f64 = self
+            .workers
+            .iter()
+            .map(|worker| worker.busy.as_secs_f64())
+            .sum();
+        return busy / (self.wall_time.as_secs_f64() * self.workers.len() as f64);
+    }
+}
+
+impl Display for SchedulerStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wall = self.wall_time.as_secs_f64();
+        for (id, worker) in self.workers.iter().enumerate() {
+            let busy = worker.busy.as_secs_f64();
+            writeln!(
+                f,
+                "thread {}: {} jobs ({} stolen), busy {:.2}s, {:.1}% utilization",
+                id,
+                worker.jobs,
+                worker.stolen,
+                busy,
+                if wall > 0.0 { busy / wall * 100.0 } else { 0.0 }
+            )?;
+        }
+        write!(
+            f,
+            "{} jobs in {:.2}s, {:.1}% average utilization",
+            self.jobs(),
+            wall,
+            self.utilization() * 100.0
+        )
+    }
+}
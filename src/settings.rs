@@ -1,9 +1,93 @@
-pub const WINDOW_HEIGHT: u32 = 600;
-pub const WINDOW_WIDTH: u32 = ((WINDOW_HEIGHT as f64) * ASPECT_RATIO) as u32;
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    integrator::IntegratorKind,
+    sampler::SamplerKind,
+    tile::{Tile, TileOrder},
+};
+
 pub const CAMERA_HEIGHT: f64 = 2.0;
-pub const ASPECT_RATIO: f64 = 1.0 / 1.0;
-pub const FOV: f64 = 40.0;
-pub const SAMPLES_PER_PIXEL: usize = 1;
-pub const RAY_DEPTH: i32 = 20;
-pub const THREAD_NUM: usize = 4;
-pub const FILTER_STEP: usize = 4;
+
+mod output_settings_impl;
+mod render_settings_impl;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RenderSettings {
+    pub width: u32,
+    pub height: u32,
+    // vertical field of view in degrees
+    pub fov: f64,
+    // samples every pixel ends up with, the upper bound in adaptive mode
+    pub samples_per_pixel: usize,
+    // samples added per progressive pass, all samples are taken in one pass when None
+    pub pass_spp: Option<usize>,
+    // stop sampling converged pixels, every pixel is sampled equally when None
+    pub adaptive: Option<AdaptiveSettings>,
+    pub budget: RenderBudget,
+    // part of the image to render, the rest stays empty. lets several
+    // machines share one image, whole image when None
+    pub region: Option<Tile>,
+    pub ray_depth: i32,
+    // bounces every path takes before russian roulette may end it, roulette
+    // is off when this is not below ray_depth
+    pub roulette_depth: i32,
+    pub thread_num: usize,
+    pub filter_step: usize,
+    // fixed seed for reproducible images, a random one is drawn per render when None
+    pub seed: Option<u64>,
+    pub sampler: SamplerKind,
+    // what a camera ray is turned into, the path tracer or a cheaper / debug view
+    pub integrator: IntegratorKind,
+    // edge length of the square tiles handed to the render threads
+    pub tile_size: u32,
+    pub tile_order: TileOrder,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveSettings {
+    // a pixel is done once the standard error of its mean luminance drops
+    // below this fraction of the mean
+    pub threshold: f64,
+    // samples taken before the variance is trusted
+    pub min_spp: usize,
+}
+
+// limits after which a render stops and keeps what it has, checked before
+// every pixel
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderBudget {
+    pub time: Option<Duration>,
+    // samples taken over the whole image
+    pub samples: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DenoiseStage {
+    OutlierRemoval,
+    RowFilter,
+    ColFilter,
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputSettings {
+    // final image, intermediate images are written next to it
+    pub path: PathBuf,
+    pub save_intermediate: bool,
+    pub denoise_stages: Vec<DenoiseStage>,
+    // minimum time between two writes of the current estimate during a
+    // progressive render, nothing is written when None
+    pub progress_interval: Option<Duration>,
+    // also write the number of samples taken per pixel as a heatmap
+    pub save_heatmap: bool,
+    // also write the normals and distances of the g-buffer as images
+    pub save_gbuffer: bool,
+    pub checkpoint: Option<CheckpointSettings>,
+}
+
+// where and how often the accumulated samples are saved between passes, a
+// final checkpoint is written when the render ends
+#[derive(Debug, Clone)]
+pub struct CheckpointSettings {
+    pub path: PathBuf,
+    pub interval: Duration,
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use super::{DenoiseStage, OutputSettings};
+
+impl DenoiseStage {
+    pub const DEFAULT_PIPELINE: [DenoiseStage; 5] = [
+        DenoiseStage::OutlierRemoval,
+        DenoiseStage::RowFilter,
+        DenoiseStage::OutlierRemoval,
+        DenoiseStage::ColFilter,
+        DenoiseStage::OutlierRemoval,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DenoiseStage::OutlierRemoval => "outlier-removal",
+            DenoiseStage::RowFilter => "row-filter",
+            DenoiseStage::ColFilter => "col-filter",
+        }
+    }
+}
+
+impl OutputSettings {
+    pub fn intermediate_path(&self, label: &str, num: usize, spp: usize) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        return self
+            .path
+            .with_file_name(format!("{}-0{}-{}SPP-{}.png", stem, num, spp, label));
+    }
+
+    // raw estimate written between progressive passes
+    pub fn progress_path(&self) -> PathBuf {
+        return self.suffixed_path("progress");
+    }
+
+    pub fn heatmap_path(&self) -> PathBuf {
+        return self.suffixed_path("spp");
+    }
+
+    pub fn normal_path(&self) -> PathBuf {
+        return self.suffixed_path("normal");
+    }
+
+    pub fn depth_path(&self) -> PathBuf {
+        return self.suffixed_path("depth");
+    }
+
+    fn suffixed_path(&self, suffix: &str) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        return self.path.with_file_name(format!("{}-{}.png", stem, suffix));
+    }
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        OutputSettings {
+            path: PathBuf::from("output.png"),
+            save_intermediate: false,
+            denoise_stages: DenoiseStage::DEFAULT_PIPELINE.to_vec(),
+            progress_interval: None,
+            save_heatmap: false,
+            save_gbuffer: false,
+            checkpoint: None,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use crate::{integrator::IntegratorKind, sampler::SamplerKind, tile::TileOrder};
+
+use super::{AdaptiveSettings, RenderBudget, RenderSettings};
+
+impl RenderSettings {
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+}
+
+impl RenderBudget {
+    pub fn is_exhausted(&self, elapsed: Duration, samples: usize) -> bool {
+        return self.time.is_some_and(|time| elapsed >= time)
+            || self.samples.is_some_and(|budget| samples >= budget);
+    }
+}
+
+impl Default for AdaptiveSettings {
+    fn default() -> Self {
+        AdaptiveSettings {
+            threshold: 0.05,
+            min_spp: 8,
+        }
+    }
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            width: 600,
+            height: 600,
+            fov: 40.0,
+            samples_per_pixel: 1,
+            pass_spp: None,
+            adaptive: None,
+            budget: RenderBudget::default(),
+            region: None,
+            ray_depth: 20,
+            roulette_depth: 3,
+            thread_num: 4,
+            filter_step: 4,
+            seed: None,
+            sampler: SamplerKind::Sobol,
+            integrator: IntegratorKind::Path,
+            tile_size: 32,
+            tile_order: TileOrder::Hilbert,
+        }
+    }
+}
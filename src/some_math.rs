@@ -1,5 +1,3 @@
-use crate::settings::{FILTER_STEP, WINDOW_HEIGHT, WINDOW_WIDTH};
-
 mod matrix_impl;
 mod vector_impl;
 
@@ -53,11 +51,16 @@ pub fn reflect(vec: &Vector3, normal: &Vector3) -> Vector3 {
 pub fn refract(vec: &Vector3, normal: &Vector3, factor: f64) -> Vector3 {
     let cos_theta = ((-1.0) * (*vec) * (*normal)).min(1.0);
     let r_out_perp = factor * (*vec + cos_theta * (*normal));
-    let r_out_para = (-1.0) * (1.0 - r_out_perp.length_square()).abs().sqrt() * (*normal);
+    let r_out_para = -(1.0 - r_out_perp.length_square()).abs().sqrt() * (*normal);
     return (r_out_para + r_out_perp).normalize();
 }
 
-pub fn generate_neighbor_pixel_coordinate(col_num: usize, row_num: usize) -> Vec<(usize, usize)> {
+pub fn generate_neighbor_pixel_coordinate(
+    col_num: usize,
+    row_num: usize,
+    width: usize,
+    height: usize,
+) -> Vec<(usize, usize)> {
     let mut res = Vec::with_capacity(48);
     for col_modifier in (-3)..4 {
         for row_modifier in (-3)..4 {
@@ -66,7 +69,7 @@ pub fn generate_neighbor_pixel_coordinate(col_num: usize, row_num: usize) -> Vec
             }
             let col = col_num as i32 + col_modifier;
             let row = row_num as i32 + row_modifier;
-            if col < 0 || col >= WINDOW_WIDTH as i32 || row < 0 || row >= WINDOW_HEIGHT as i32 {
+            if col < 0 || col >= width as i32 || row < 0 || row >= height as i32 {
                 continue;
             }
             res.push((col as usize, row as usize));
@@ -75,9 +78,9 @@ pub fn generate_neighbor_pixel_coordinate(col_num: usize, row_num: usize) -> Vec
     return res;
 }
 
-pub fn num_inline(list: &Vec<[f64; 3]>, target: [f64; 3]) -> [f64; 3] {
+pub fn num_inline(list: &[[f64; 3]], target: [f64; 3]) -> [f64; 3] {
     let l = list.len();
-    let mut res = target.clone();
+    let mut res = target;
     let mut r_vec = Vec::with_capacity(l);
     let mut g_vec = Vec::with_capacity(l);
     let mut b_vec = Vec::with_capacity(l);
@@ -113,16 +116,21 @@ pub fn num_inline(list: &Vec<[f64; 3]>, target: [f64; 3]) -> [f64; 3] {
     return res;
 }
 
-pub fn generate_num_sequence(base_num: usize, step: usize) -> Vec<usize> {
+pub fn generate_num_sequence(
+    base_num: usize,
+    step: usize,
+    filter_step: usize,
+    len: usize,
+) -> Vec<usize> {
     let mut res = Vec::with_capacity(6);
     let interval = (step as f64).exp2() as i32;
-    for num in 1..FILTER_STEP as i32 {
+    for num in 1..filter_step as i32 {
         let n0 = base_num as i32 - interval * num;
         let n1 = base_num as i32 + interval * num;
         if n0 >= 0 {
             res.push(n0 as usize);
         }
-        if n1 < WINDOW_WIDTH as i32 {
+        if n1 < len as i32 {
             res.push(n1 as usize);
         }
     }
@@ -1,7 +1,6 @@
 use std::{
     fmt::Display,
     ops::{Add, AddAssign, Div, DivAssign, Mul, Sub},
-    usize,
 };
 
 use super::{clamp, Color, Vector3};
@@ -68,6 +67,15 @@ impl Vector3 {
         return Vector3 { data };
     }
 
+    pub fn max_component(&self) -> f64 {
+        self.data[0].max(self.data[1]).max(self.data[2])
+    }
+
+    // relative luminance of a linear rgb color
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.data[0] + 0.7152 * self.data[1] + 0.0722 * self.data[2]
+    }
+
     pub fn get_axis(&self) -> usize {
         let mut n = 0;
         if self.data[1].abs() > 1e-3 {
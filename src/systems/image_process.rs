@@ -1,8 +1,162 @@
 use crate::{
-    data::GBInfo,
-    some_math::{Color, Point, Vector3},
+    data::{FilterType, GBInfo, GeometryBuffer, PixelContainer, RowColPixels},
+    scheduler::Scheduler,
+    settings::DenoiseStage,
+    some_math::{
+        generate_neighbor_pixel_coordinate, generate_num_sequence, num_inline, sum_vector_list,
+        Color, Point, Vector3,
+    },
 };
 
+pub fn denoise(
+    stage: DenoiseStage,
+    pixels: &PixelContainer,
+    gbuffer: &GeometryBuffer,
+    filter_step: usize,
+    scheduler: &Scheduler,
+) -> PixelContainer {
+    match stage {
+        DenoiseStage::OutlierRemoval => outlier_removal(pixels, scheduler),
+        DenoiseStage::RowFilter => {
+            filter_image(pixels, gbuffer, FilterType::Row, filter_step, scheduler)
+        }
+        DenoiseStage::ColFilter => {
+            filter_image(pixels, gbuffer, FilterType::Col, filter_step, scheduler)
+        }
+    }
+}
+
+pub fn outlier_removal(raw_data: &PixelContainer, scheduler: &Scheduler) -> PixelContainer {
+    println!("==> Removing outlier");
+    let width = raw_data.width();
+    let height = raw_data.height();
+    // one job per row
+    let (rows, _) = scheduler.run(
+        (0..height).collect(),
+        |_| (),
+        |_, row_num| {
+            let mut row_res = RowColPixels::new(width);
+            for col_num in 0..width {
+                let mut colors_vec = Vec::new();
+                for (col, row) in
+                    generate_neighbor_pixel_coordinate(col_num, row_num, width, height)
+                {
+                    colors_vec.push(raw_data.get_colors(col, row));
+                }
+                row_res.set_color(
+                    col_num,
+                    num_inline(&colors_vec, raw_data.get_colors(col_num, row_num)),
+                );
+            }
+            row_res
+        },
+        |_, _| {},
+    );
+    let mut res_vec = PixelContainer::new(width, height);
+    for (row_num, row) in rows.into_iter().enumerate() {
+        let row = row.unwrap_or_else(|| raw_data.get_x_or_y(row_num, FilterType::Row));
+        res_vec.set_row(row_num, row);
+    }
+    return res_vec;
+}
+
+pub fn filter_image(
+    input_pixels: &PixelContainer,
+    input_gbuffer: &GeometryBuffer,
+    filter_type: FilterType,
+    filter_step: usize,
+    scheduler: &Scheduler,
+) -> PixelContainer {
+    let x_axis_total_num;
+    let y_axis_total_num;
+    let label;
+    match filter_type {
+        FilterType::Row => {
+            x_axis_total_num = input_pixels.width();
+            y_axis_total_num = input_pixels.height();
+            label = "Row-filter".to_string();
+        }
+        FilterType::Col => {
+            x_axis_total_num = input_pixels.height();
+            y_axis_total_num = input_pixels.width();
+            label = "Col-filter".to_string();
+        }
+    }
+    println!("==> {} filtering...", label);
+    // one job per row / col
+    let (lines, _) = scheduler.run(
+        (0..y_axis_total_num).collect(),
+        |_| (),
+        |_, y_value| {
+            filter_line(
+                input_pixels,
+                input_gbuffer,
+                filter_type,
+                filter_step,
+                y_value,
+                x_axis_total_num,
+            )
+        },
+        |_, _| {},
+    );
+    let mut res_vec = PixelContainer::new(input_pixels.width(), input_pixels.height());
+    for (y_value, line) in lines.into_iter().enumerate() {
+        let line = line.unwrap_or_else(|| input_pixels.get_x_or_y(y_value, filter_type));
+        for x_value in 0..x_axis_total_num {
+            res_vec.set_colors(x_value, y_value, line.get_color(x_value).data, filter_type);
+        }
+    }
+    return res_vec;
+}
+
+fn filter_line(
+    input_pixels: &PixelContainer,
+    input_gbuffer: &GeometryBuffer,
+    filter_type: FilterType,
+    filter_step: usize,
+    y_value: usize,
+    x_axis_total_num: usize,
+) -> RowColPixels {
+    let mut res_line = RowColPixels::new(x_axis_total_num);
+    let y_axis_pixels = input_pixels.get_x_or_y(y_value, filter_type);
+    let y_axis_gbuffer = input_gbuffer.get_x_or_y(y_value, filter_type);
+    for x_value in 0..x_axis_total_num {
+        let gb0 = y_axis_gbuffer.get_data(x_value);
+        let c0 = y_axis_pixels.get_color(x_value);
+        let mut weights = 1.0;
+        let mut res_pixel = c0;
+        for step in 0..filter_step {
+            let sample_points = generate_num_sequence(x_value, step, filter_step, x_axis_total_num);
+            let mut color_vec = Vec::new();
+            color_vec.push(c0);
+            for temp_sample in sample_points.iter() {
+                color_vec.push(y_axis_pixels.get_color(*temp_sample));
+            }
+            let temp_l = color_vec.len();
+            let color_mean = sum_vector_list(&color_vec) / temp_l as f64;
+            let color_sigma = (color_vec
+                .iter()
+                .map(|c| (*c - color_mean).length_square())
+                .sum::<f64>()
+                / (temp_l - 1) as f64)
+                .sqrt();
+
+            for sample in sample_points {
+                let c1 = y_axis_pixels.get_color(sample);
+                let gb1 = y_axis_gbuffer.get_data(sample);
+                let w = pixel_filter(gb0, gb1, c0, c1, color_sigma);
+                weights += w;
+                res_pixel += w * c1;
+            }
+        }
+        if weights > 0.0 {
+            res_pixel /= weights;
+        }
+        res_line.set_color(x_value, res_pixel.data);
+    }
+    return res_line;
+}
+
 pub fn pixel_filter(gb0: &GBInfo, gb1: &GBInfo, c0: Color, c1: Color, sigma: f64) -> f64 {
     if gb0.hit_obj_id != gb1.hit_obj_id {
         return 0.0;
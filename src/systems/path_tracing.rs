@@ -1,142 +1,187 @@
-use std::{f64::INFINITY, sync::Arc};
-
-use rand::prelude::ThreadRng;
+use std::sync::Arc;
 
 use crate::{
+    accel::Bvh,
     data::{GBInfo, HitInfo},
-    entity::{
-        obj_traits::{Hittable, HittableLight},
-        Ray,
-    },
-    material::Material,
-    settings::RAY_DEPTH,
+    entity::{obj_traits::HittableLight, Ray},
+    sampler::Sampler,
     some_math::{Color, Point, Vector3},
 };
 
+// iterative path tracer, `gbuffer_data` is filled from the first hit when given
 pub fn shade(
     ray_in: &Ray,
-    objects: &Vec<Arc<dyn Hittable + Send + Sync>>,
-    lights: &Vec<Arc<dyn HittableLight + Send + Sync>>,
-    depth: i32,
-    rng: &mut ThreadRng,
-    dismiss_light: bool,
-    gb_indicator: bool,
-    gbuffer_data: &mut GBInfo,
+    objects: &Bvh,
+    lights: &[Arc<dyn HittableLight + Send + Sync>],
+    max_depth: i32,
+    roulette_depth: i32,
+    sampler: &mut dyn Sampler,
+    mut gbuffer_data: Option<&mut GBInfo>,
 ) -> Color {
-    if depth < 0 {
-        return Color::BLACK;
-    }
-    let mut dl = dismiss_light;
-    if depth == RAY_DEPTH {
-        dl = false;
-    }
-    if let Some(info) = ray_hit(ray_in, objects, dl) {
-        if gb_indicator {
-            *gbuffer_data = GBInfo {
-                distance: (ray_in.at(info.t) - ray_in.origin).length(),
-                normal: info.normal,
-                hit_point: info.hit_point,
-                hit_obj_id: info.obj_id,
+    let mut radiance = Color::BLACK;
+    let mut throughput = Color::new([1.0; 3]);
+    let mut ray = *ray_in;
+    // solid angle pdf the last bounce drew `ray` with, None for camera rays
+    // and delta lobes, whose emission is taken as is since light sampling
+    // can not produce them
+    let mut bsdf_pdf: Option<f64> = None;
+    for bounce in 0..=max_depth {
+        let info = match objects.ray_hit(&ray, false) {
+            Some(info) => info,
+            None => break,
+        };
+        if let Some(gbuffer_data) = gbuffer_data.take() {
+            *gbuffer_data = GBInfo::from_hit(&ray, &info);
+        }
+        let material = &info.material;
+        let wo = (-1.0) * ray.direction;
+        radiance += throughput.naive_mul(emitted(&ray, &info, lights, bsdf_pdf));
+        if material.is_light() {
+            break;
+        }
+        // delta lobes can not be hit by a light sample
+        if material.flags().is_non_specular() {
+            radiance += throughput.naive_mul(sample_lights(&info, &wo, objects, lights, sampler));
+        }
+        let bsdf_sample = match material.sample(&wo, &info.normal, sampler) {
+            Some(bsdf_sample) => bsdf_sample,
+            None => break,
+        };
+        throughput = throughput.naive_mul(bsdf_sample.weight);
+        // russian roulette on the path throughput, survivors are scaled up
+        // so the estimate stays unbiased
+        if roulette_depth < max_depth && bounce + 1 >= roulette_depth {
+            let survival = throughput.max_component().min(0.95);
+            if sampler.get_1d() >= survival {
+                break;
             }
+            throughput /= survival;
         }
-        return shade_point(
-            ray_in,
-            &info.hit_point,
-            &info.material,
-            &info.normal,
-            objects,
-            lights,
-            depth - 1,
-            rng,
-            dl,
-        );
+        ray = Ray::new(info.hit_point, bsdf_sample.wi);
+        bsdf_pdf = if bsdf_sample.flags.is_specular() {
+            None
+        } else {
+            Some(bsdf_sample.pdf)
+        };
     }
-    return Color::BLACK;
+    return radiance;
 }
 
-fn ray_hit(
-    ray_in: &Ray,
-    // objects: &Arc<Vec<Arc<dyn Hittable + Send + Sync>>>,
-    objects: &Vec<Arc<dyn Hittable + Send + Sync>>,
-    dismiss_light: bool,
-) -> Option<HitInfo> {
-    let mut t = INFINITY;
-    let mut hit_info = None;
-    for obj in objects.iter() {
-        if dismiss_light && obj.is_light() {
-            continue;
-        }
-        if let Some(info) = obj.ray_intersect(ray_in) {
-            if info.t < t {
-                t = info.t;
-                hit_info = Some(info);
-            }
+// emission seen along `ray`, weighted against light sampling when the ray
+// came from a bsdf sample
+pub fn emitted(
+    ray: &Ray,
+    info: &HitInfo,
+    lights: &[Arc<dyn HittableLight + Send + Sync>],
+    bsdf_pdf: Option<f64>,
+) -> Color {
+    // lights emit on the side of their normal only, as light sampling assumes
+    if !info.material.is_light() || ray.direction * info.normal >= 0.0 {
+        return Color::BLACK;
+    }
+    let mut weight = 1.0;
+    if let Some(bsdf_pdf) = bsdf_pdf {
+        if let Some(light) = info.light.map(|index| &lights[index]) {
+            let pdf = light_pdf(light, &ray.origin, &info.hit_point, &info.normal);
+            weight = power_heuristic(bsdf_pdf, pdf);
         }
     }
-    return hit_info;
+    return info.material.emit() * weight;
 }
 
-fn shade_point(
+// direct lighting alone: light samples plus one bsdf sample that only looks
+// for emitters, combined with mis
+pub fn direct_lighting(
     ray_in: &Ray,
-    point: &Point,
-    point_material: &Arc<dyn Material>,
-    point_normal: &Vector3,
-    // objects: &Arc<Vec<Arc<dyn Hittable + Send + Sync>>>,
-    objects: &Vec<Arc<dyn Hittable + Send + Sync>>,
-    lights: &Vec<Arc<dyn HittableLight + Send + Sync>>,
-    depth: i32,
-    rng: &mut ThreadRng,
-    dismiss_light: bool,
+    info: &HitInfo,
+    objects: &Bvh,
+    lights: &[Arc<dyn HittableLight + Send + Sync>],
+    sampler: &mut dyn Sampler,
 ) -> Color {
-    let mut shade_color = point_material.emit();
-    if point_material.is_light() {
-        return shade_color;
+    let wo = (-1.0) * ray_in.direction;
+    let mut radiance = Color::BLACK;
+    if info.material.flags().is_non_specular() {
+        radiance += sample_lights(info, &wo, objects, lights, sampler);
+    }
+    if let Some(bsdf_sample) = info.material.sample(&wo, &info.normal, sampler) {
+        let ray = Ray::new(info.hit_point, bsdf_sample.wi);
+        let bsdf_pdf = if bsdf_sample.flags.is_specular() {
+            None
+        } else {
+            Some(bsdf_sample.pdf)
+        };
+        if let Some(hit) = objects.ray_hit(&ray, false) {
+            radiance += bsdf_sample
+                .weight
+                .naive_mul(emitted(&ray, &hit, lights, bsdf_pdf));
+        }
     }
-    // naive ray tracing
-    let fresnel_factor = point_material.get_fresnel(&ray_in.direction, point_normal);
-    let albedo = point_material.get_color();
-    // direct shading
+    return radiance;
+}
+
+// one area sample on every light, weighted against bsdf sampling
+fn sample_lights(
+    info: &HitInfo,
+    wo: &Vector3,
+    objects: &Bvh,
+    lights: &[Arc<dyn HittableLight + Send + Sync>],
+    sampler: &mut dyn Sampler,
+) -> Color {
+    let point = &info.hit_point;
+    let point_normal = &info.normal;
+    let mut shade_color = Color::BLACK;
     for light in lights.iter() {
-        let pdf_mul = light.get_pdf_mul();
-        let (sample_point, sample_normal) = light.sample_on_light(rng);
+        let (sample_point, sample_normal) = light.sample_on_light(sampler);
         let sample_point_to_point = *point - sample_point;
-        if sample_point_to_point * (*point_normal) >= 0.0
-            || sample_point_to_point * sample_normal <= 0.0
-        {
+        // lights only emit on the side of their normal
+        if sample_point_to_point * sample_normal <= 0.0 {
             continue;
         }
         let length_square = sample_point_to_point.length_square();
         let unit_sptp = sample_point_to_point.normalize();
+        let wi = (-1.0) * unit_sptp;
+        let f = info.material.eval(wo, &wi, point_normal);
+        if f.luminance() <= 0.0 {
+            continue;
+        }
         let temp_ray = Ray::new(sample_point, unit_sptp);
-        if let Some(thing) = ray_hit(&temp_ray, objects, true) {
-            if (thing.hit_point - sample_point).length_square() < length_square {
-                continue;
-            }
+        // stop just short of the shaded point so it does not shadow itself
+        if objects.occluded(&temp_ray, length_square.sqrt() * (1.0 - 1e-6), true) {
+            continue;
+        }
+        let pdf = light_pdf(light, point, &sample_point, &sample_normal);
+        if pdf <= 0.0 {
+            continue;
         }
+        let weight = power_heuristic(pdf, info.material.pdf(wo, &wi, point_normal));
         let cos_theta = (unit_sptp * (*point_normal)).abs();
-        let cos_theta_prime = (unit_sptp * sample_normal).abs();
-        shade_color += albedo.naive_mul(light.get_light_color()) * cos_theta * cos_theta_prime
-            / length_square
-            * pdf_mul
-            * fresnel_factor
-            * 0.5;
+        shade_color += f.naive_mul(light.get_light_color()) * (cos_theta * weight / pdf);
     }
-    // indirect shading
-    let scatter_info = point_material.scatter(ray_in, point_normal, rng);
-    let scatter_ray = Ray::new(*point, scatter_info.scatter_dir);
-    shade_color += albedo.naive_mul(shade(
-        &scatter_ray,
-        objects,
-        lights,
-        depth,
-        rng,
-        dismiss_light,
-        false,
-        &mut GBInfo::default(),
-    )) * ((*point_normal) * scatter_info.scatter_dir).abs()
-        / scatter_info.pdf
-        * fresnel_factor
-        * 0.5;
-    // )) / scatter_info.pdf;
     return shade_color;
 }
+
+// power heuristic with beta = 2, one sample taken from each strategy
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b <= 0.0 {
+        return 0.0;
+    }
+    return a / (a + b);
+}
+
+// solid angle pdf of sampling `point_on_light` by area, seen from `point`
+fn light_pdf(
+    light: &Arc<dyn HittableLight + Send + Sync>,
+    point: &Point,
+    point_on_light: &Point,
+    light_normal: &Vector3,
+) -> f64 {
+    let to_light = *point_on_light - *point;
+    let length_square = to_light.length_square();
+    let cos_theta_prime = (to_light.normalize() * (*light_normal)).abs();
+    let area = light.get_pdf_mul().abs();
+    if cos_theta_prime <= 0.0 || area <= 0.0 {
+        return 0.0;
+    }
+    return length_square / (cos_theta_prime * area);
+}
@@ -1,7 +1,5 @@
 use std::f64::consts::PI;
 
-use rand::{prelude::ThreadRng, Rng};
-
 use crate::some_math::{Matrix3, Vector3};
 
 pub fn rotate_around_y(vector: Vector3, angle: f64) -> Vector3 {
@@ -25,9 +23,9 @@ pub fn rotate_vec_given_normal(vec: &Vector3, normal: &Vector3) -> Vector3 {
     return res;
 }
 
-pub fn generate_unit_vec_sphere(rng: &mut ThreadRng) -> Vector3 {
-    let theta = rng.gen_range(0.0..PI);
-    let phi = rng.gen_range(0.0..(2.0 * PI));
+pub fn generate_unit_vec_sphere(sample: [f64; 2]) -> Vector3 {
+    let theta = sample[0] * PI;
+    let phi = sample[1] * 2.0 * PI;
     let trans_rotate_z = matrix_rotate_around_z(theta.sin(), theta.cos());
     let trans_rotate_y = matrix_rotate_around_y(phi.sin(), phi.cos());
     return trans_rotate_y * (trans_rotate_z * Vector3::new([0.0, 1.0, 0.0]));
@@ -0,0 +1,22 @@
+mod order_impl;
+mod tile_impl;
+
+// rectangle of the image in pixels, the unit of work handed to a render thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// order in which the tiles of a region are queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileOrder {
+    // left to right, top to bottom
+    Scanline,
+    // outwards from the center tile
+    Spiral,
+    // along a hilbert curve, neighboring jobs stay close in the image
+    Hilbert,
+}
@@ -0,0 +1,79 @@
+use super::TileOrder;
+
+impl TileOrder {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TileOrder::Scanline => "scanline",
+            TileOrder::Spiral => "spiral",
+            TileOrder::Hilbert => "hilbert",
+        }
+    }
+
+    // every cell of a cols x rows grid exactly once
+    pub fn grid_sequence(&self, cols: u32, rows: u32) -> Vec<(u32, u32)> {
+        let total = cols as usize * rows as usize;
+        let mut res = Vec::with_capacity(total);
+        match self {
+            TileOrder::Scanline => {
+                for row in 0..rows {
+                    for col in 0..cols {
+                        res.push((col, row));
+                    }
+                }
+            }
+            TileOrder::Spiral => {
+                // walk right 1, down 1, left 2, up 2, right 3 ... from the center,
+                // keeping the cells that fall inside the grid
+                let (mut col, mut row) = ((cols as i64 - 1) / 2, (rows as i64 - 1) / 2);
+                let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+                let mut leg = 0;
+                while res.len() < total {
+                    let (dx, dy) = directions[leg % 4];
+                    let steps = leg / 2 + 1;
+                    for _ in 0..steps {
+                        if col >= 0 && col < cols as i64 && row >= 0 && row < rows as i64 {
+                            res.push((col as u32, row as u32));
+                        }
+                        col += dx;
+                        row += dy;
+                    }
+                    leg += 1;
+                }
+            }
+            TileOrder::Hilbert => {
+                // curve over the enclosing power of two square, cells outside are skipped
+                let side = cols.max(rows).max(1).next_power_of_two();
+                for index in 0..side as u64 * side as u64 {
+                    let (col, row) = hilbert_to_grid(side, index);
+                    if col < cols && row < rows {
+                        res.push((col, row));
+                    }
+                }
+            }
+        }
+        return res;
+    }
+}
+
+// position of the index-th cell on a hilbert curve filling a side x side square
+fn hilbert_to_grid(side: u32, index: u64) -> (u32, u32) {
+    let (mut x, mut y) = (0u64, 0u64);
+    let mut t = index;
+    let mut s = 1u64;
+    while s < side as u64 {
+        let rx = 1 & (t / 2);
+        let ry = 1 & (t ^ rx);
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - x;
+                y = s - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        x += s * rx;
+        y += s * ry;
+        t /= 4;
+        s *= 2;
+    }
+    return (x as u32, y as u32);
+}
@@ -0,0 +1,58 @@
+use super::{Tile, TileOrder};
+
+impl Tile {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Tile {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn pixel_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    // overlapping part of two tiles, zero sized when they do not overlap
+    pub fn intersect(&self, other: &Tile) -> Tile {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        return Tile {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        };
+    }
+
+    // pixel coordinates in row major order
+    pub fn pixels(&self) -> impl Iterator<Item = (u32, u32)> {
+        let tile = *self;
+        (tile.y..tile.y + tile.height)
+            .flat_map(move |row| (tile.x..tile.x + tile.width).map(move |col| (col, row)))
+    }
+
+    // cut the region into tiles of at most tile_size x tile_size pixels
+    pub fn split(&self, tile_size: u32, order: TileOrder) -> Vec<Tile> {
+        let tile_size = tile_size.max(1);
+        let cols = self.width.div_ceil(tile_size);
+        let rows = self.height.div_ceil(tile_size);
+        return order
+            .grid_sequence(cols, rows)
+            .into_iter()
+            .map(|(col, row)| {
+                let x = self.x + col * tile_size;
+                let y = self.y + row * tile_size;
+                Tile {
+                    x,
+                    y,
+                    width: tile_size.min(self.x + self.width - x),
+                    height: tile_size.min(self.y + self.height - y),
+                }
+            })
+            .collect();
+    }
+}
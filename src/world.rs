@@ -4,18 +4,37 @@ use std::{
 };
 
 use crate::{
+    accel::Bvh,
     camera::Camera,
+    data::FrameBuffer,
     entity::obj_traits::{Hittable, HittableLight},
+    scheduler::CancelToken,
+    settings::RenderSettings,
 };
 
+use remote::WorkerPool;
+
+mod distributed_impl;
 mod job_distribution;
-mod multithread_impl;
+mod remote;
 mod world_impl;
 
+pub use remote::RemoteError;
+
 pub struct World {
     start_time: SystemTime,
     last_end_time: SystemTime,
     objects: Arc<RwLock<Vec<Arc<dyn Hittable + Send + Sync>>>>,
     camera: Arc<Camera>,
     lights: Arc<RwLock<Vec<Arc<dyn HittableLight + Send + Sync>>>>,
+    settings: RenderSettings,
+    // rebuilt lazily whenever the object list changes
+    bvh: Option<Arc<Bvh>>,
+    cancel: CancelToken,
+    // samples accumulated so far, dropped whenever the scene or camera changes
+    frame: Option<FrameBuffer>,
+    // seed the accumulated samples were taken with
+    seed: u64,
+    // worker processes tiles are handed to, rendered here when None
+    workers: Option<Arc<WorkerPool>>,
 }
@@ -0,0 +1,225 @@
+use std::{
+    io::{self, ErrorKind},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    path::Path,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    data::FrameBuffer,
+    scheduler::{CancelToken, Scheduler},
+    settings::RenderSettings,
+    tile::{Tile, TileOrder},
+};
+
+use super::{
+    job_distribution::{process_job_sequence, RenderContext, TileJob},
+    remote::{Message, RemoteError, WorkerPool},
+    World,
+};
+
+// a worker splits its tile again so all its threads get a share
+const WORKER_TILE_SIZE: u32 = 8;
+// workers may be started before the coordinator listens
+const CONNECT_ATTEMPTS: usize = 50;
+const CONNECT_DELAY: Duration = Duration::from_millis(200);
+// well below the coordinator's silence timeout
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+impl World {
+    // renders from now on hand their tiles to worker processes connecting to
+    // `addr` instead of rendering them here, `thread_num` tiles are out at once.
+    // returns the address actually bound
+    pub fn serve_workers<A: ToSocketAddrs>(&mut self, addr: A) -> io::Result<SocketAddr> {
+        let pool = WorkerPool::listen(addr)?;
+        let local_addr = pool.local_addr();
+        self.workers = Some(pool);
+        return Ok(local_addr);
+    }
+
+    // tells the connected workers to exit, later renders run locally again
+    pub fn release_workers(&mut self) {
+        if let Some(pool) = self.workers.take() {
+            pool.shutdown();
+        }
+    }
+
+    // worker side: connects to a coordinator, loads `scene` (the built-in one
+    // when None) with the settings it sends and renders its jobs until it is
+    // told to stop. returns the number of jobs rendered
+    pub fn work<A: ToSocketAddrs, P: AsRef<Path>>(
+        addr: A,
+        scene: Option<P>,
+        thread_num: usize,
+    ) -> Result<usize, RemoteError> {
+        let mut stream = connect(addr)?;
+        stream.set_nodelay(true)?;
+        println!("==> Connected to {}", stream.peer_addr()?);
+        let mut world: Option<World> = None;
+        let mut job_num = 0;
+        loop {
+            let message = match Message::read_from(&mut stream) {
+                Ok(message) => message,
+                // the coordinator went away, nothing more to do
+                Err(RemoteError::Io(err)) if err.kind() == ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            };
+            match message {
+                Message::Setup { settings, seed } => {
+                    // loading a large scene may take longer than the
+                    // coordinator waits for a silent worker
+                    let next = heartbeat_while(&stream, || {
+                        let mut next = World::new(RenderSettings {
+                            thread_num,
+                            ..settings
+                        });
+                        match &scene {
+                            Some(path) => next.load_scene(path).map_err(RemoteError::Scene)?,
+                            None => next.default_scene(),
+                        }
+                        next.build_bvh();
+                        next.seed = seed;
+                        next.frame = Some(FrameBuffer::new(
+                            settings.width as usize,
+                            settings.height as usize,
+                        ));
+                        return Ok::<_, RemoteError>(next);
+                    })??;
+                    Message::Ready {
+                        object_count: next.object_count(),
+                    }
+                    .write_to(&mut stream)?;
+                    world = Some(next);
+                }
+                Message::Job(job) => {
+                    let world = world.as_ref().ok_or_else(|| {
+                        RemoteError::Protocol("job before the render setup".to_string())
+                    })?;
+                    let done = heartbeat_while(&stream, || world.render_job(&job))??;
+                    Message::Done(done).write_to(&mut stream)?;
+                    job_num += 1;
+                }
+                Message::Finish => break,
+                _ => {
+                    return Err(RemoteError::Protocol(
+                        "unexpected message from the coordinator".to_string(),
+                    ))
+                }
+            }
+        }
+        println!("==> Done, {} jobs rendered", job_num);
+        return Ok(job_num);
+    }
+
+    // worker side of one job, the tile is rendered with all local threads
+    fn render_job(&self, job: &TileJob) -> Result<TileJob, RemoteError> {
+        let frame = self.frame.as_ref().unwrap();
+        let bvh = self.bvh.as_ref().unwrap();
+        let lights = self.lights.read().unwrap().clone();
+        let settings = self.settings;
+        let camera = self.camera.as_ref();
+        job.check_fits(frame, settings.samples_per_pixel)
+            .map_err(RemoteError::Protocol)?;
+        job.store(frame);
+        let context = RenderContext {
+            camera,
+            objects: bvh,
+            lights: &lights,
+            settings: &settings,
+            frame,
+        };
+        let scheduler = Scheduler::new(settings.thread_num, CancelToken::new());
+        scheduler.run(
+            job.tile.split(WORKER_TILE_SIZE, TileOrder::Scanline),
+            |_| {
+                settings
+                    .sampler
+                    .build(self.seed, settings.samples_per_pixel)
+            },
+            |sampler, tile| {
+                process_job_sequence(&context, tile, sampler.as_mut(), job.sample_end, &|_| false);
+            },
+            |_, _| {},
+        );
+        return Ok(TileJob::capture(frame, job.tile, job.sample_end));
+    }
+}
+
+// coordinator side of one tile: runs it on the next idle worker and moves on
+// to another one when that worker disappears. returns the samples taken, 0
+// when `should_stop` gave up waiting for a worker, None when no worker is
+// left and the tile has to be rendered locally
+pub fn render_remote(
+    pool: &WorkerPool,
+    setup: &Message,
+    object_count: usize,
+    frame: &FrameBuffer,
+    tile: Tile,
+    sample_end: usize,
+    should_stop: &dyn Fn(usize) -> bool,
+) -> Option<usize> {
+    let job = TileJob::capture(frame, tile, sample_end);
+    loop {
+        let mut connection = match pool.acquire(&|| should_stop(0) || pool.is_deserted()) {
+            Some(connection) => connection,
+            None if pool.is_deserted() => return None,
+            None => return Some(0),
+        };
+        match connection.render(setup, object_count, &job) {
+            Ok(done) => {
+                pool.release(connection);
+                done.store(frame);
+                return Some(done.sample_count().saturating_sub(job.sample_count()));
+            }
+            // the job goes to the next worker
+            Err(err) => {
+                println!(
+                    "==> Worker {} lost ({}), handing its tile to another worker",
+                    connection.peer(),
+                    err
+                );
+                pool.lose(connection);
+            }
+        }
+    }
+}
+
+// runs `work` while another thread tells the coordinator every
+// HEARTBEAT_INTERVAL that this worker is still busy
+fn heartbeat_while<T, F: FnOnce() -> T>(stream: &TcpStream, work: F) -> Result<T, RemoteError> {
+    let mut heartbeat_stream = stream.try_clone()?;
+    let (finished, wait) = mpsc::channel::<()>();
+    return Ok(thread::scope(|scope| {
+        scope.spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = wait.recv_timeout(HEARTBEAT_INTERVAL) {
+                // a broken connection shows up again when the result is sent
+                if Message::Heartbeat.write_to(&mut heartbeat_stream).is_err() {
+                    break;
+                }
+            }
+        });
+        let res = work();
+        // wakes the heartbeat thread, the scope waits for it before the
+        // result goes out on the same stream
+        drop(finished);
+        res
+    }));
+}
+
+fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<TcpStream> {
+    let mut attempt = 1;
+    loop {
+        match TcpStream::connect(&addr) {
+            Ok(stream) => return Ok(stream),
+            Err(err)
+                if attempt < CONNECT_ATTEMPTS && err.kind() == ErrorKind::ConnectionRefused =>
+            {
+                attempt += 1;
+                thread::sleep(CONNECT_DELAY);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
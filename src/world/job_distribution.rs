@@ -1,55 +1,168 @@
 use std::sync::Arc;
 
-use rand::prelude::ThreadRng;
-
 use crate::{
+    accel::Bvh,
     camera::Camera,
-    data::{FilterType, GBInfo, RowColGBuffer, RowColPixels},
-    entity::obj_traits::{Hittable, HittableLight},
-    settings::{RAY_DEPTH, SAMPLES_PER_PIXEL, WINDOW_WIDTH},
-    systems::path_tracing::shade,
+    data::{FrameBuffer, GBInfo, PIXEL_WORDS},
+    entity::obj_traits::HittableLight,
+    sampler::Sampler,
+    settings::RenderSettings,
+    some_math::Color,
+    tile::Tile,
 };
 
+// a tile together with the state of its pixels, all a worker process needs to
+// continue them up to `sample_end`. the worker sends the same job back with the
+// new state
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileJob {
+    pub tile: Tile,
+    pub sample_end: usize,
+    // PIXEL_WORDS per pixel, row major
+    pub words: Vec<u64>,
+}
+
+impl TileJob {
+    pub fn capture(frame: &FrameBuffer, tile: Tile, sample_end: usize) -> Self {
+        let mut words = Vec::with_capacity(tile.pixel_count() * PIXEL_WORDS);
+        for (col_num, row_num) in tile.pixels() {
+            words.extend_from_slice(&frame.pixel_words(col_num as usize, row_num as usize));
+        }
+        return TileJob {
+            tile,
+            sample_end,
+            words,
+        };
+    }
+
+    pub fn store(&self, frame: &FrameBuffer) {
+        for ((col_num, row_num), words) in self.tile.pixels().zip(self.words.chunks(PIXEL_WORDS)) {
+            frame.set_pixel_words(col_num as usize, row_num as usize, words);
+        }
+    }
+
+    // a job from the coordinator has to lie inside the worker's frame and
+    // stay within the samples per pixel of the render
+    pub fn check_fits(&self, frame: &FrameBuffer, max_spp: usize) -> Result<(), String> {
+        let right = self.tile.x as usize + self.tile.width as usize;
+        let bottom = self.tile.y as usize + self.tile.height as usize;
+        if right > frame.width() || bottom > frame.height() {
+            return Err(format!(
+                "job for {}x{} pixels at ({}, {}) outside the {}x{} image",
+                self.tile.width,
+                self.tile.height,
+                self.tile.x,
+                self.tile.y,
+                frame.width(),
+                frame.height()
+            ));
+        }
+        if self.sample_end > max_spp {
+            return Err(format!(
+                "job up to {} samples, the render takes {}",
+                self.sample_end, max_spp
+            ));
+        }
+        return Ok(());
+    }
+
+    // a worker's answer has to be this job continued: same tile and sample
+    // end, and no pixel may have lost samples
+    pub fn check_done(&self, done: &TileJob) -> Result<(), String> {
+        if done.tile != self.tile {
+            return Err("answer for another tile".to_string());
+        }
+        if done.sample_end != self.sample_end {
+            return Err(format!(
+                "answer up to {} samples, the job was up to {}",
+                done.sample_end, self.sample_end
+            ));
+        }
+        let before = self.words.chunks(PIXEL_WORDS).map(|words| words[4]);
+        let after = done.words.chunks(PIXEL_WORDS).map(|words| words[4]);
+        if done.words.len() != self.words.len() || before.zip(after).any(|(a, b)| b < a) {
+            return Err("answer lost samples of the job".to_string());
+        }
+        return Ok(());
+    }
+
+    // samples held by the pixels of the job, the difference before and after
+    // rendering is what the worker took
+    pub fn sample_count(&self) -> usize {
+        return self
+            .words
+            .chunks(PIXEL_WORDS)
+            .map(|words| words[4] as usize)
+            .sum();
+    }
+}
+
+// everything a render thread reads while it works on its tiles
+#[derive(Clone, Copy)]
+pub struct RenderContext<'a> {
+    pub camera: &'a Camera,
+    pub objects: &'a Bvh,
+    pub lights: &'a [Arc<dyn HittableLight + Send + Sync>],
+    pub settings: &'a RenderSettings,
+    pub frame: &'a FrameBuffer,
+}
+
+// renders one tile straight into the shared frame buffer, every pixel that still
+// needs samples continues from the ones it holds up to `sample_end`. `stop` is
+// asked before every pixel with the samples taken so far, the tile is left
+// unfinished once it returns true. returns the number of samples taken
 pub fn process_job_sequence(
-    content: u32,
-    camera: Arc<Camera>,
-    objects: &Vec<Arc<dyn Hittable + Send + Sync>>,
-    lights: &Vec<Arc<dyn HittableLight + Send + Sync>>,
-    rng: &mut ThreadRng,
-) -> (u32, RowColPixels, RowColGBuffer) {
-    let mut pixel_res = RowColPixels::new(FilterType::Row);
-    let mut gbuffer_res = RowColGBuffer::new_empty(FilterType::Col);
-    for col_num in 0..WINDOW_WIDTH {
-        let ray_list = camera.generate_rays(col_num, content, rng);
-        let mut gbuffer_data = GBInfo::default();
-        let mut pixel_color = shade(
-            &ray_list[0],
-            objects,
-            lights,
-            RAY_DEPTH,
-            rng,
-            false,
-            true,
-            &mut gbuffer_data,
-        );
-        if SAMPLES_PER_PIXEL > 1 {
-            for index in 1..SAMPLES_PER_PIXEL {
-                let ray = &ray_list[index];
-                pixel_color += shade(
-                    ray,
-                    objects,
-                    lights,
-                    RAY_DEPTH,
-                    rng,
-                    false,
-                    false,
-                    &mut GBInfo::default(),
-                );
+    context: &RenderContext,
+    tile: Tile,
+    sampler: &mut dyn Sampler,
+    sample_end: usize,
+    stop: &dyn Fn(usize) -> bool,
+) -> usize {
+    let RenderContext {
+        camera,
+        objects,
+        lights,
+        settings,
+        frame,
+    } = *context;
+    let integrator = settings.integrator.build(settings);
+    let mut sample_num = 0;
+    for (col_num, row_num) in tile.pixels() {
+        if stop(sample_num) {
+            break;
+        }
+        let (col, row) = (col_num as usize, row_num as usize);
+        let sample_start = frame.sample_count(col, row);
+        if !frame.needs_samples(col, row, sample_end, settings.adaptive.as_ref()) {
+            continue;
+        }
+        // samples are added one by one, so the sum does not depend on how
+        // the samples were split into passes
+        let mut pixel_sum = Color::new(frame.radiance_sum(col, row));
+        let mut square_sum = frame.luminance_square_sum(col, row);
+        for sample in sample_start..sample_end {
+            sampler.start_sample(col_num, row_num, sample);
+            let ray = camera.generate_ray(col_num, row_num, sample, sampler);
+            let mut gbuffer_data = GBInfo::default();
+            let color = integrator.radiance(
+                &ray,
+                objects,
+                lights,
+                sampler,
+                if sample == 0 {
+                    Some(&mut gbuffer_data)
+                } else {
+                    None
+                },
+            );
+            pixel_sum += color;
+            square_sum += color.luminance() * color.luminance();
+            if sample == 0 {
+                frame.set_gbuffer(col, row, &gbuffer_data);
             }
-            pixel_color /= SAMPLES_PER_PIXEL as f64;
         }
-        pixel_res.set_color(col_num as usize, pixel_color.data);
-        gbuffer_res.push_data(gbuffer_data);
+        frame.set_radiance_sum(col, row, pixel_sum.data, square_sum, sample_end);
+        sample_num += sample_end - sample_start;
     }
-    return (content, pixel_res, gbuffer_res);
+    return sample_num;
 }
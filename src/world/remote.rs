@@ -0,0 +1,79 @@
+use std::{
+    fmt::Display,
+    io,
+    net::{SocketAddr, TcpStream},
+    sync::{atomic::AtomicUsize, Condvar, Mutex},
+};
+
+use crate::{scene::SceneError, settings::RenderSettings};
+
+use super::job_distribution::TileJob;
+
+mod pool_impl;
+mod protocol_impl;
+
+// what coordinator and workers exchange. every message is framed as a tag
+// byte and a u32 payload length, numbers are little endian
+#[derive(Debug, Clone)]
+pub enum Message {
+    // settings and seed of the render, sent before the first job and again
+    // whenever they change. the thread count is left to the worker
+    Setup { settings: RenderSettings, seed: u64 },
+    // the worker has loaded its scene
+    Ready { object_count: usize },
+    Job(TileJob),
+    Done(TileJob),
+    // sent by a worker every few seconds while it renders a job, so a slow
+    // tile is not taken for a lost worker
+    Heartbeat,
+    // no more jobs, the worker exits
+    Finish,
+}
+
+// one worker process as seen by the coordinator
+pub struct Connection {
+    stream: TcpStream,
+    peer: SocketAddr,
+    // encoded setup the worker has loaded, None before the first one
+    setup: Option<Vec<u8>>,
+}
+
+// connections of the worker processes, accepted on a background thread. a
+// connection is taken out for one job at a time and put back afterwards
+pub struct WorkerPool {
+    idle: Mutex<Vec<Connection>>,
+    available: Condvar,
+    // workers connected and not lost, idle or busy
+    live: AtomicUsize,
+    // workers lost so far, none live after some were lost means they all are
+    lost: AtomicUsize,
+    local_addr: SocketAddr,
+}
+
+#[derive(Debug)]
+pub enum RemoteError {
+    Io(io::Error),
+    Protocol(String),
+    Scene(SceneError),
+}
+
+impl Display for RemoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                write!(f, "connection closed")
+            }
+            RemoteError::Io(err) => write!(f, "connection failed: {}", err),
+            RemoteError::Protocol(message) => write!(f, "protocol error: {}", message),
+            RemoteError::Scene(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
+impl From<io::Error> for RemoteError {
+    fn from(err: io::Error) -> Self {
+        RemoteError::Io(err)
+    }
+}
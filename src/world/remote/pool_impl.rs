@@ -0,0 +1,175 @@
+use std::{
+    io,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::world::job_distribution::TileJob;
+
+use super::{Connection, Message, RemoteError, WorkerPool};
+
+// a worker silent for this long is considered gone. busy workers send a
+// heartbeat well within it, however long their tile takes
+const SILENCE_TIMEOUT: Duration = Duration::from_secs(60);
+// how often a thread waiting for a worker checks whether it should give up
+const WAIT_STEP: Duration = Duration::from_millis(100);
+
+impl WorkerPool {
+    pub fn listen<A: ToSocketAddrs>(addr: A) -> io::Result<Arc<WorkerPool>> {
+        let listener = TcpListener::bind(addr)?;
+        let pool = Arc::new(WorkerPool {
+            idle: Mutex::new(Vec::new()),
+            available: Default::default(),
+            live: AtomicUsize::new(0),
+            lost: AtomicUsize::new(0),
+            local_addr: listener.local_addr()?,
+        });
+        let acceptor = pool.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let res = stream.and_then(|stream| acceptor.add(stream));
+                if let Err(err) = res {
+                    eprintln!("warning: cannot accept worker: {}", err);
+                }
+            }
+        });
+        return Ok(pool);
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    fn add(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(SILENCE_TIMEOUT))?;
+        let peer = stream.peer_addr()?;
+        println!("==> Worker {} connected", peer);
+        self.live.fetch_add(1, Ordering::Relaxed);
+        self.release(Connection {
+            stream,
+            peer,
+            setup: None,
+        });
+        return Ok(());
+    }
+
+    // waits for an idle worker, None once `give_up` returns true
+    pub fn acquire(&self, give_up: &dyn Fn() -> bool) -> Option<Connection> {
+        let mut idle = self.lock();
+        loop {
+            if let Some(connection) = idle.pop() {
+                return Some(connection);
+            }
+            if give_up() {
+                return None;
+            }
+            idle = self
+                .available
+                .wait_timeout(idle, WAIT_STEP)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    // drops a connection that failed, the last one lost is reported
+    pub fn lose(&self, connection: Connection) {
+        drop(connection);
+        self.lost.fetch_add(1, Ordering::Relaxed);
+        if self.live.fetch_sub(1, Ordering::Relaxed) == 1 {
+            println!("==> No workers left, rendering the remaining tiles here");
+        }
+    }
+
+    // every worker that connected is lost, until another one connects
+    pub fn is_deserted(&self) -> bool {
+        self.live.load(Ordering::Relaxed) == 0 && self.lost.load(Ordering::Relaxed) > 0
+    }
+
+    pub fn release(&self, connection: Connection) {
+        self.lock().push(connection);
+        self.available.notify_one();
+    }
+
+    // tells the idle workers to exit
+    pub fn shutdown(&self) {
+        for mut connection in self.lock().drain(..) {
+            // a worker that is already gone does not need telling
+            let _ = Message::Finish.write_to(&mut connection.stream);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Connection>> {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Connection {
+    pub fn peer(&self) -> SocketAddr {
+        self.peer
+    }
+
+    // runs one job on the worker, any error means the worker cannot be used
+    // any more and the job has to go to another one
+    pub fn render(
+        &mut self,
+        setup: &Message,
+        object_count: usize,
+        job: &TileJob,
+    ) -> Result<TileJob, RemoteError> {
+        let (_, setup_payload) = setup.encode();
+        if self.setup.as_ref() != Some(&setup_payload) {
+            setup.write_to(&mut self.stream)?;
+            match read_skipping_heartbeats(&mut self.stream)? {
+                Message::Ready {
+                    object_count: worker_count,
+                } if worker_count == object_count => {}
+                Message::Ready {
+                    object_count: worker_count,
+                } => {
+                    return Err(RemoteError::Protocol(format!(
+                        "worker loaded {} objects, the scene has {}",
+                        worker_count, object_count
+                    )));
+                }
+                other => return Err(unexpected(&other)),
+            }
+            self.setup = Some(setup_payload);
+        }
+        Message::Job(job.clone()).write_to(&mut self.stream)?;
+        match read_skipping_heartbeats(&mut self.stream)? {
+            Message::Done(done) => {
+                job.check_done(&done).map_err(RemoteError::Protocol)?;
+                return Ok(done);
+            }
+            other => return Err(unexpected(&other)),
+        }
+    }
+}
+
+// the worker sends heartbeats while it loads its scene or renders a job
+fn read_skipping_heartbeats(stream: &mut TcpStream) -> Result<Message, RemoteError> {
+    loop {
+        match Message::read_from(stream)? {
+            Message::Heartbeat => continue,
+            message => return Ok(message),
+        }
+    }
+}
+
+fn unexpected(message: &Message) -> RemoteError {
+    let name = match message {
+        Message::Setup { .. } => "setup",
+        Message::Ready { .. } => "ready",
+        Message::Job(_) => "job",
+        Message::Done(_) => "done",
+        Message::Finish => "finish",
+        Message::Heartbeat => "heartbeat",
+    };
+    return RemoteError::Protocol(format!("unexpected {} message", name));
+}
@@ -0,0 +1,213 @@
+use std::io::{Read, Write};
+
+use crate::{
+    data::PIXEL_WORDS,
+    integrator::IntegratorKind,
+    sampler::SamplerKind,
+    settings::{AdaptiveSettings, RenderSettings},
+    tile::Tile,
+    world::job_distribution::TileJob,
+};
+
+use super::{Message, RemoteError};
+
+const TAG_SETUP: u8 = 1;
+const TAG_READY: u8 = 2;
+const TAG_JOB: u8 = 3;
+const TAG_DONE: u8 = 4;
+const TAG_FINISH: u8 = 5;
+const TAG_HEARTBEAT: u8 = 6;
+// far above any tile, keeps a broken peer from asking for any allocation
+const MAX_PAYLOAD: u32 = 1 << 28;
+
+impl Message {
+    pub fn write_to<W: Write>(&self, out: &mut W) -> Result<(), RemoteError> {
+        let (tag, payload) = self.encode();
+        out.write_all(&[tag])?;
+        out.write_all(&(payload.len() as u32).to_le_bytes())?;
+        out.write_all(&payload)?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    pub fn read_from<R: Read>(input: &mut R) -> Result<Message, RemoteError> {
+        let mut head = [0; 5];
+        input.read_exact(&mut head)?;
+        let len = u32::from_le_bytes([head[1], head[2], head[3], head[4]]);
+        if len > MAX_PAYLOAD {
+            return Err(RemoteError::Protocol(format!("message of {} bytes", len)));
+        }
+        let mut payload = vec![0; len as usize];
+        input.read_exact(&mut payload)?;
+        return Message::decode(head[0], &payload);
+    }
+
+    // (tag, payload), also used to tell whether a worker has a setup loaded
+    pub fn encode(&self) -> (u8, Vec<u8>) {
+        let mut payload = Vec::new();
+        let mut put = |value: u64| payload.extend_from_slice(&value.to_le_bytes());
+        let tag = match self {
+            Message::Setup { settings, seed } => {
+                let adaptive = settings.adaptive;
+                for value in [
+                    settings.width as u64,
+                    settings.height as u64,
+                    settings.fov.to_bits(),
+                    settings.samples_per_pixel as u64,
+                    settings.ray_depth as i64 as u64,
+                    settings.roulette_depth as i64 as u64,
+                    *seed,
+                    adaptive.is_some() as u64,
+                    adaptive.map_or(0, |adaptive| adaptive.threshold.to_bits()),
+                    adaptive.map_or(0, |adaptive| adaptive.min_spp as u64),
+                ] {
+                    put(value);
+                }
+                for label in [settings.sampler.label(), settings.integrator.label()] {
+                    payload.push(label.len() as u8);
+                    payload.extend_from_slice(label.as_bytes());
+                }
+                TAG_SETUP
+            }
+            Message::Ready { object_count } => {
+                put(*object_count as u64);
+                TAG_READY
+            }
+            Message::Job(job) | Message::Done(job) => {
+                let tile = job.tile;
+                for value in [
+                    tile.x as u64,
+                    tile.y as u64,
+                    tile.width as u64,
+                    tile.height as u64,
+                    job.sample_end as u64,
+                ] {
+                    put(value);
+                }
+                for word in job.words.iter() {
+                    put(*word);
+                }
+                if let Message::Job(_) = self {
+                    TAG_JOB
+                } else {
+                    TAG_DONE
+                }
+            }
+            Message::Finish => TAG_FINISH,
+            Message::Heartbeat => TAG_HEARTBEAT,
+        };
+        return (tag, payload);
+    }
+
+    fn decode(tag: u8, payload: &[u8]) -> Result<Message, RemoteError> {
+        let mut reader = PayloadReader { payload, pos: 0 };
+        let message = match tag {
+            TAG_SETUP => {
+                let width = reader.u64()? as u32;
+                let height = reader.u64()? as u32;
+                let fov = f64::from_bits(reader.u64()?);
+                let samples_per_pixel = reader.u64()? as usize;
+                let ray_depth = reader.u64()? as i64 as i32;
+                let roulette_depth = reader.u64()? as i64 as i32;
+                let seed = reader.u64()?;
+                let has_adaptive = reader.u64()? != 0;
+                let threshold = f64::from_bits(reader.u64()?);
+                let min_spp = reader.u64()? as usize;
+                let label_len = reader.bytes(1)?[0] as usize;
+                let label = String::from_utf8_lossy(reader.bytes(label_len)?).to_string();
+                let sampler = SamplerKind::from_label(&label)
+                    .ok_or_else(|| RemoteError::Protocol(format!("unknown sampler {}", label)))?;
+                let label_len = reader.bytes(1)?[0] as usize;
+                let label = String::from_utf8_lossy(reader.bytes(label_len)?).to_string();
+                let integrator = IntegratorKind::from_label(&label).ok_or_else(|| {
+                    RemoteError::Protocol(format!("unknown integrator {}", label))
+                })?;
+                Message::Setup {
+                    settings: RenderSettings {
+                        width,
+                        height,
+                        fov,
+                        samples_per_pixel,
+                        ray_depth,
+                        roulette_depth,
+                        seed: Some(seed),
+                        sampler,
+                        integrator,
+                        adaptive: if has_adaptive {
+                            Some(AdaptiveSettings { threshold, min_spp })
+                        } else {
+                            None
+                        },
+                        ..RenderSettings::default()
+                    },
+                    seed,
+                }
+            }
+            TAG_READY => Message::Ready {
+                object_count: reader.u64()? as usize,
+            },
+            TAG_JOB | TAG_DONE => {
+                let tile = Tile::new(
+                    reader.u64()? as u32,
+                    reader.u64()? as u32,
+                    reader.u64()? as u32,
+                    reader.u64()? as u32,
+                );
+                let sample_end = reader.u64()? as usize;
+                let word_num = tile.pixel_count() * PIXEL_WORDS;
+                if payload.len() - reader.pos != word_num * 8 {
+                    return Err(RemoteError::Protocol(format!(
+                        "job payload does not match a {}x{} tile",
+                        tile.width, tile.height
+                    )));
+                }
+                let mut words = Vec::with_capacity(word_num);
+                for _ in 0..word_num {
+                    words.push(reader.u64()?);
+                }
+                let job = TileJob {
+                    tile,
+                    sample_end,
+                    words,
+                };
+                if tag == TAG_JOB {
+                    Message::Job(job)
+                } else {
+                    Message::Done(job)
+                }
+            }
+            TAG_FINISH => Message::Finish,
+            TAG_HEARTBEAT => Message::Heartbeat,
+            _ => return Err(RemoteError::Protocol(format!("unknown message {}", tag))),
+        };
+        if reader.pos != payload.len() {
+            return Err(RemoteError::Protocol(
+                "trailing bytes after message".to_string(),
+            ));
+        }
+        return Ok(message);
+    }
+}
+
+struct PayloadReader<'a> {
+    payload: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PayloadReader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], RemoteError> {
+        if self.payload.len() - self.pos < len {
+            return Err(RemoteError::Protocol("message too short".to_string()));
+        }
+        let bytes = &self.payload[self.pos..self.pos + len];
+        self.pos += len;
+        return Ok(bytes);
+    }
+
+    fn u64(&mut self) -> Result<u64, RemoteError> {
+        let bytes = self.bytes(8)?;
+        let mut value = [0; 8];
+        value.copy_from_slice(bytes);
+        return Ok(u64::from_le_bytes(value));
+    }
+}
@@ -1,216 +1,464 @@
 use std::{
-    sync::{Arc, RwLock},
-    time::SystemTime,
-    u32,
+    collections::BTreeMap,
+    io,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
-use image::{ImageBuffer, Rgb};
+use image::{ImageBuffer, ImageResult, Rgb};
 
 use crate::{
+    accel::{Bvh, BvhStats},
     camera::Camera,
-    data::{FilterType, GeometryBuffer, PixelContainer},
-    entity::{obj_traits::Hittable, Panel, Rectangle, Sphere},
-    material::{DiffuseLight, DiffuseMat, Glass, Metal},
-    settings::{FILTER_STEP, SAMPLES_PER_PIXEL, THREAD_NUM, WINDOW_HEIGHT, WINDOW_WIDTH},
-    some_math::{
-        generate_neighbor_pixel_coordinate, generate_num_sequence, num_inline, sum_vector_list,
-        Color, Point, Vector3,
+    checkpoint::{
+        merge_checkpoints, read_checkpoint, write_checkpoint, CheckpointError, CheckpointInfo,
+    },
+    data::{FrameBuffer, GeometryBuffer, PixelContainer},
+    entity::{
+        obj_traits::{Hittable, HittableLight},
+        Panel, Rectangle, Sphere, Triangle,
+    },
+    material::{DiffuseLight, DiffuseMat, Glass, Material, Metal},
+    sampler::SamplerKind,
+    scene::{load_obj, MaterialDesc, MeshTransform, ObjectDesc, SceneDesc, SceneError},
+    scheduler::{CancelToken, Scheduler},
+    settings::{OutputSettings, RenderSettings},
+    some_math::{Color, Point, Vector3},
+    systems::image_process::denoise,
+    tile::Tile,
+    world::{
+        distributed_impl::render_remote,
+        job_distribution::{process_job_sequence, RenderContext},
+        remote::Message,
     },
-    systems::image_process::pixel_filter,
-    world::multithread_impl::ThreadPool,
 };
 
 use super::World;
 
 impl World {
-    pub fn new() -> Self {
+    pub fn new(settings: RenderSettings) -> Self {
         World {
             start_time: SystemTime::now(),
             last_end_time: SystemTime::now(),
             objects: Arc::new(RwLock::new(Vec::new())),
             lights: Arc::new(RwLock::new(Vec::new())),
             camera: Arc::new(Camera::default()),
+            settings,
+            bvh: None,
+            cancel: CancelToken::new(),
+            frame: None,
+            seed: 0,
+            workers: None,
         }
     }
 
-    pub fn run(&mut self) {
-        self.start_time = SystemTime::now();
-        let (raw_pixel, gbuffer) = self.shade_pixel();
-        let proc_pixel_0 = self.outlier_removal(raw_pixel, 1);
-        let proc_pixel_1 = self.row_filter(proc_pixel_0, gbuffer.clone());
-        // let proc_pixel_1 = self.col_filter(proc_pixel_0, gbuffer.clone());
-        let proc_pixel_2 = self.outlier_removal(proc_pixel_1, 3);
-        let proc_pixel_3 = self.col_filter(proc_pixel_2, gbuffer);
-        // let prec_pixel_3 = self.row_filter(proc_pixel_2, gbuffer);
-        self.outlier_removal(proc_pixel_3, 5);
+    // cancels the running render from another thread, the tiles already
+    // started are finished and the partial image is returned
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    pub fn settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = Arc::new(camera);
+        self.frame = None;
+    }
+
+    // raising the sample count keeps the accumulated samples, the next
+    // render only adds the missing ones
+    pub fn set_samples_per_pixel(&mut self, samples_per_pixel: usize) {
+        self.settings.samples_per_pixel = samples_per_pixel;
+    }
+
+    // samples every pixel holds so far
+    pub fn samples_done(&self) -> usize {
+        self.frame
+            .as_ref()
+            .map_or(0, |frame| frame.min_sample_count(&self.region()))
     }
 
-    fn shade_pixel(&mut self) -> (PixelContainer, GeometryBuffer) {
-        // fn shade_pixel(&mut self) {
-        println!("==> Starting shading...");
-        let thread_pool = ThreadPool::new(
-            THREAD_NUM,
-            self.camera.clone(),
-            self.objects.clone(),
-            self.lights.clone(),
+    pub fn reset_accumulation(&mut self) {
+        self.frame = None;
+    }
+
+    pub fn object_count(&self) -> usize {
+        self.objects.read().unwrap().len()
+    }
+
+    pub fn add(&mut self, obj: Arc<dyn Hittable + Send + Sync>) {
+        self.objects.write().unwrap().push(obj);
+        self.bvh = None;
+        self.frame = None;
+    }
+
+    pub fn add_light(&mut self, light: Arc<dyn HittableLight + Send + Sync>) {
+        self.objects.write().unwrap().push(light.clone());
+        self.lights.write().unwrap().push(light);
+        self.bvh = None;
+        self.frame = None;
+    }
+
+    pub fn build_bvh(&mut self) -> BvhStats {
+        let bvh = Bvh::build(&self.objects.read().unwrap(), &self.lights.read().unwrap());
+        let stats = *bvh.stats();
+        println!(
+            "==> BVH built: {} objects, {} nodes, {} leaves, depth {}, {} ms",
+            stats.objects,
+            stats.nodes,
+            stats.leaves,
+            stats.depth,
+            stats.build_time.as_millis()
         );
-        for job in 0..WINDOW_HEIGHT {
-            thread_pool.work(job);
-        }
-        let res = self.res_process(&thread_pool);
-        thread_pool.shut_down();
+        self.bvh = Some(Arc::new(bvh));
+        return stats;
+    }
 
-        self.save_image(&res.0, "origin-img".to_string(), 0);
-        return res;
+    pub fn bvh_stats(&self) -> Option<BvhStats> {
+        self.bvh.as_ref().map(|bvh| *bvh.stats())
     }
 
-    fn res_process(&self, thread_pool: &ThreadPool) -> (PixelContainer, GeometryBuffer) {
-        let mut pixel_res = PixelContainer::new();
-        let mut gbuffer_res = GeometryBuffer::new();
-        let mut num = 0;
-        let mut last_portion = 0;
-        'job_loop: loop {
-            if let Ok(job_res) = thread_pool.result.recv() {
-                let row_num = job_res.0 as usize;
-                let row_content = job_res.1.clone();
-                let gb_row_content = job_res.2.clone();
-                pixel_res.set_row(row_num, row_content);
-                gbuffer_res.set_row(row_num, gb_row_content);
-                num += 1;
-                let portion = ((num as f64 / WINDOW_HEIGHT as f64) * 100.0) as u32;
-                if portion > last_portion {
-                    println!("{}% done.", portion);
-                    last_portion = portion;
+    pub fn run(&mut self, output: &OutputSettings) -> ImageResult<()> {
+        self.start_time = SystemTime::now();
+        self.last_end_time = self.start_time;
+        self.init_frame();
+        let info = self.checkpoint_info();
+        let mut last_write = SystemTime::now();
+        let mut last_checkpoint = SystemTime::now();
+        self.render_progressive(|frame| {
+            let due = |last: SystemTime, interval: Duration| {
+                last.elapsed().unwrap_or_default() >= interval
+            };
+            // failed writes in between are not worth stopping the render for
+            if let Some(interval) = output.progress_interval {
+                if due(last_write, interval) {
+                    let path = output.progress_path();
+                    if let Err(err) = save_pixels(&frame.to_pixel_container(), &path) {
+                        eprintln!("warning: cannot write {}: {}", path.display(), err);
+                    }
+                    last_write = SystemTime::now();
                 }
             }
-            if num == WINDOW_HEIGHT {
-                break 'job_loop;
+            if let Some(checkpoint) = &output.checkpoint {
+                if due(last_checkpoint, checkpoint.interval) {
+                    if let Err(err) = write_checkpoint(&checkpoint.path, &info, frame) {
+                        eprintln!(
+                            "warning: cannot write checkpoint {}: {}",
+                            checkpoint.path.display(),
+                            err
+                        );
+                    }
+                    last_checkpoint = SystemTime::now();
+                }
             }
+        });
+        if let Some(checkpoint) = &output.checkpoint {
+            println!("Saving checkpoint to {}..", checkpoint.path.display());
+            self.save_checkpoint(&checkpoint.path)?;
         }
-        return (pixel_res, gbuffer_res);
+        return self.finish(output);
     }
 
-    fn outlier_removal(&mut self, raw_data: PixelContainer, indicator: usize) -> PixelContainer {
-        println!("==> Removing outlier");
-        let mut res_vec = PixelContainer::new();
-        for row_num in 0..WINDOW_HEIGHT as usize {
-            for col_num in 0..WINDOW_WIDTH as usize {
-                let mut colors_vec = Vec::new();
-                for (col, row) in generate_neighbor_pixel_coordinate(col_num, row_num) {
-                    colors_vec.push(raw_data.get_colors(col, row));
-                }
-                res_vec.set_colors(
-                    col_num,
-                    row_num,
-                    num_inline(&colors_vec, raw_data.get_colors(col_num, row_num)),
-                    FilterType::Row,
-                );
+    // runs the accumulated image through the denoise pipeline and writes the
+    // results without rendering anything, `run` ends here and merged partial
+    // renders start here
+    pub fn finish(&mut self, output: &OutputSettings) -> ImageResult<()> {
+        self.init_frame();
+        let frame = self.frame.as_ref().unwrap();
+        let (mut pixels, gbuffer) = (frame.to_pixel_container(), frame.to_geometry_buffer());
+        let heatmap = frame.sample_heatmap(
+            self.settings
+                .samples_per_pixel
+                .max(frame.max_sample_count()),
+        );
+        if output.save_gbuffer {
+            println!("Saving g-buffer images..");
+            self.save_rgb(gbuffer.to_normal_pixels(), &output.normal_path())?;
+            self.save_rgb(gbuffer.to_depth_pixels(), &output.depth_path())?;
+        }
+        if output.save_heatmap {
+            let path = output.heatmap_path();
+            println!("Saving sample heatmap to {}..", path.display());
+            self.save_rgb(heatmap, &path)?;
+        }
+        self.finish_stage("origin-img", 0, &pixels, output)?;
+        // denoise passes always run to the end, cancellation is checked between them
+        let scheduler = Scheduler::new(self.settings.thread_num, CancelToken::new());
+        for (index, stage) in output.denoise_stages.iter().enumerate() {
+            if self.cancel.is_cancelled() {
+                println!("==> Cancelled, skipping the remaining denoise stages");
+                break;
             }
+            pixels = denoise(
+                *stage,
+                &pixels,
+                &gbuffer,
+                self.settings.filter_step,
+                &scheduler,
+            );
+            self.finish_stage(stage.label(), index + 1, &pixels, output)?;
         }
+        println!("Saving final image to {}..", output.path.display());
+        return self.save_image(&pixels, &output.path);
+    }
 
-        self.save_image(&res_vec, "outlier-removal".to_string(), indicator);
-        return res_vec;
+    // creates the accumulation buffer and draws the seed of a new render
+    fn init_frame(&mut self) {
+        if self.frame.is_none() {
+            self.seed = self.settings.seed.unwrap_or_else(rand::random);
+            self.frame = Some(FrameBuffer::new(
+                self.settings.width as usize,
+                self.settings.height as usize,
+            ));
+        }
     }
 
-    fn row_filter(
-        &mut self,
-        processed_img: PixelContainer,
-        gbuffer: GeometryBuffer,
-    ) -> PixelContainer {
-        // row process
-        let res_vec = self.filter_image(&processed_img, &gbuffer, FilterType::Row);
-        self.save_image(&res_vec, "row-filter".to_string(), 2);
-        return res_vec;
+    pub fn checkpoint_info(&self) -> CheckpointInfo {
+        CheckpointInfo {
+            width: self.settings.width as usize,
+            height: self.settings.height as usize,
+            seed: self.seed,
+            sampler: self.settings.sampler,
+            samples_per_pixel: self.settings.samples_per_pixel,
+            object_count: self.object_count(),
+            region: self.region(),
+        }
     }
 
-    fn col_filter(
+    // part of the image this world renders, clipped to the image
+    fn region(&self) -> Tile {
+        let full = Tile::new(0, 0, self.settings.width, self.settings.height);
+        return self
+            .settings
+            .region
+            .map_or(full, |region| region.intersect(&full));
+    }
+
+    // writes the accumulated samples, nothing is written before the first render
+    pub fn save_checkpoint<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        return match &self.frame {
+            Some(frame) => write_checkpoint(path, &self.checkpoint_info(), frame),
+            None => Ok(()),
+        };
+    }
+
+    // continues the render stored in a checkpoint, the scene and camera have to
+    // be set up before since changing them drops the samples again. returns
+    // the samples every pixel holds already
+    pub fn resume<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, CheckpointError> {
+        let (info, frame) = read_checkpoint(path)?;
+        let current = self.checkpoint_info();
+        let mismatch = if (info.width, info.height) != (current.width, current.height) {
+            Some(format!(
+                "it is {}x{}, the render is {}x{}",
+                info.width, info.height, current.width, current.height
+            ))
+        } else if info.sampler != current.sampler {
+            Some(format!("it uses the {} sampler", info.sampler.label()))
+        } else if info.sampler == SamplerKind::Stratified
+            && info.samples_per_pixel != current.samples_per_pixel
+        {
+            Some(format!(
+                "its strata are laid out for {} spp",
+                info.samples_per_pixel
+            ))
+        } else if self.settings.seed.is_some_and(|seed| seed != info.seed) {
+            Some(format!("it uses seed {}", info.seed))
+        } else if info.region != current.region {
+            Some(format!(
+                "it covers {}x{} pixels at ({}, {})",
+                info.region.width, info.region.height, info.region.x, info.region.y
+            ))
+        } else if info.object_count != current.object_count {
+            Some(format!(
+                "it was rendered from {} objects, the scene has {}",
+                info.object_count, current.object_count
+            ))
+        } else {
+            None
+        };
+        if let Some(message) = mismatch {
+            return Err(CheckpointError::Mismatch(message));
+        }
+        let samples_done = frame.min_sample_count(&self.region());
+        self.seed = info.seed;
+        self.frame = Some(frame);
+        return Ok(samples_done);
+    }
+
+    // replaces the accumulated samples with the sum of several partial renders,
+    // the image size is taken from them. `finish` writes the merged image
+    pub fn load_merged<P: AsRef<Path>>(
         &mut self,
-        processed_img: PixelContainer,
-        gbuffer: GeometryBuffer,
-    ) -> PixelContainer {
-        let res_vec = self.filter_image(&processed_img, &gbuffer, FilterType::Col);
-        self.save_image(&res_vec, "col-filter".to_string(), 4);
-        return res_vec;
+        paths: &[P],
+    ) -> Result<CheckpointInfo, CheckpointError> {
+        let (info, frame) = merge_checkpoints(paths)?;
+        self.settings.width = info.width as u32;
+        self.settings.height = info.height as u32;
+        self.settings.region = None;
+        self.seed = info.seed;
+        self.frame = Some(frame);
+        return Ok(info);
+    }
+
+    pub fn render(&mut self) -> (PixelContainer, GeometryBuffer) {
+        return self.render_progressive(|_| {});
     }
 
-    fn filter_image(
-        &self,
-        input_pixels: &PixelContainer,
-        input_gbuffer: &GeometryBuffer,
-        filter_type: FilterType,
-    ) -> PixelContainer {
-        let x_axis_total_num;
-        let y_axis_total_num;
-        let label;
-        match filter_type {
-            FilterType::Row => {
-                x_axis_total_num = WINDOW_WIDTH;
-                y_axis_total_num = WINDOW_HEIGHT;
-                label = "Row-filter".to_string();
+    // adds passes of `pass_spp` samples until every pixel holds
+    // `samples_per_pixel`, `on_pass` sees the frame after each finished pass.
+    // cancellation or the budget stop it early with every pixel still holding
+    // a valid estimate of the samples it took
+    pub fn render_progressive<F: FnMut(&FrameBuffer)>(
+        &mut self,
+        mut on_pass: F,
+    ) -> (PixelContainer, GeometryBuffer) {
+        let start = Instant::now();
+        if self.bvh.is_none() {
+            self.build_bvh();
+        }
+        let bvh = self.bvh.clone().unwrap();
+        self.init_frame();
+        let frame = self.frame.take().unwrap();
+        let seed = self.seed;
+        println!(
+            "==> Starting shading with {} sampler, seed {}...",
+            self.settings.sampler.label(),
+            seed
+        );
+        // snapshot, no lock is held while rendering
+        let lights = self.lights.read().unwrap().clone();
+        let region = self.region();
+        let tiles = region.split(self.settings.tile_size, self.settings.tile_order);
+        let tile_num = tiles.len();
+        let settings = self.settings;
+        let camera = self.camera.as_ref();
+        let max_spp = settings.samples_per_pixel;
+        let adaptive = settings.adaptive.as_ref();
+        // adaptive sampling looks at the variance between passes, so it
+        // always renders in passes
+        let pass_spp = settings
+            .pass_spp
+            .or(adaptive.map(|adaptive| adaptive.min_spp))
+            .unwrap_or(max_spp)
+            .max(1);
+        // stops the scheduler on cancellation or once the budget is used up
+        let stop = CancelToken::new();
+        let cancel = self.cancel.clone();
+        let taken = AtomicUsize::new(0);
+        let should_stop = |tile_samples: usize| {
+            let samples = taken.load(Ordering::Relaxed) + tile_samples;
+            if cancel.is_cancelled() || settings.budget.is_exhausted(start.elapsed(), samples) {
+                stop.cancel();
             }
-            FilterType::Col => {
-                x_axis_total_num = WINDOW_HEIGHT;
-                y_axis_total_num = WINDOW_WIDTH;
-                label = "Col-filter".to_string();
+            return stop.is_cancelled();
+        };
+        let workers = self.workers.clone();
+        let setup = Message::Setup { settings, seed };
+        let object_count = self.object_count();
+        let scheduler = Scheduler::new(settings.thread_num, stop.clone());
+        // passes continue from the furthest pixel, so a resumed render keeps
+        // the pass boundaries adaptive sampling decided on
+        let context = RenderContext {
+            camera,
+            objects: &bvh,
+            lights: &lights,
+            settings: &settings,
+            frame: &frame,
+        };
+        let mut sample_end = frame.max_sample_count();
+        loop {
+            let pending = frame.pending_pixels(&region, max_spp, adaptive);
+            if pending == 0 {
+                break;
             }
-        }
-        println!("==> {} filtering...", label);
-        let mut res_vec = PixelContainer::new();
-        for y_value in 0..y_axis_total_num as usize {
-            let y_axis_pixels = input_pixels.get_x_or_y(y_value, filter_type);
-            let y_axis_gbuffer = input_gbuffer.get_x_or_y(y_value, filter_type);
-            for x_value in 0..x_axis_total_num as usize {
-                let gb0 = y_axis_gbuffer.get_data(x_value);
-                let c0 = y_axis_pixels.get_color(x_value);
-                let mut weights = 1.0;
-                let mut res_pixel = c0.clone();
-                for step in 0..FILTER_STEP {
-                    let sample_points = generate_num_sequence(x_value, step);
-                    let mut color_vec = Vec::new();
-                    color_vec.push(c0);
-                    for temp_sample in sample_points.iter() {
-                        color_vec.push(y_axis_pixels.get_color(*temp_sample));
-                    }
-                    let temp_l = color_vec.len();
-                    let color_mean = sum_vector_list(&color_vec) / temp_l as f64;
-                    let color_sigma = (color_vec
-                        .iter()
-                        .map(|c| (*c - color_mean).length_square())
-                        .sum::<f64>()
-                        / (temp_l - 1) as f64)
-                        .sqrt();
-
-                    for sample in sample_points {
-                        let c1 = y_axis_pixels.get_color(sample);
-                        let gb1 = y_axis_gbuffer.get_data(sample);
-                        let w = pixel_filter(gb0, gb1, c0, c1, color_sigma);
-                        weights += w;
-                        res_pixel += w * c1;
-                    }
-                }
-                if weights > 0.0 {
-                    res_pixel /= weights;
-                }
-                res_vec.set_colors(x_value, y_value, res_pixel.data, filter_type);
+            sample_end = (sample_end + pass_spp).min(max_spp);
+            println!("==> Pass: {} pixels up to {} samples", pending, sample_end);
+            let (_, stats) = scheduler.run(
+                tiles.clone(),
+                |_| settings.sampler.build(seed, settings.samples_per_pixel),
+                |sampler, tile| {
+                    let sample_num = match &workers {
+                        Some(pool) => render_remote(
+                            pool,
+                            &setup,
+                            object_count,
+                            &frame,
+                            tile,
+                            sample_end,
+                            &should_stop,
+                        )
+                        .unwrap_or_else(|| {
+                            process_job_sequence(
+                                &context,
+                                tile,
+                                sampler.as_mut(),
+                                sample_end,
+                                &should_stop,
+                            )
+                        }),
+                        None => process_job_sequence(
+                            &context,
+                            tile,
+                            sampler.as_mut(),
+                            sample_end,
+                            &should_stop,
+                        ),
+                    };
+                    taken.fetch_add(sample_num, Ordering::Relaxed);
+                },
+                report_progress,
+            );
+            println!("==> Render threads:\n{}", stats);
+            if stop.is_cancelled() {
+                println!(
+                    "==> Render {}, {} of {} tiles of the pass started",
+                    if cancel.is_cancelled() {
+                        "cancelled"
+                    } else {
+                        "budget used up"
+                    },
+                    stats.jobs(),
+                    tile_num
+                );
+                break;
             }
+            on_pass(&frame);
         }
-        return res_vec;
+        if adaptive.is_some() {
+            println!(
+                "==> Adaptive sampling: {:.2} samples per pixel on average",
+                frame.total_sample_count() as f64 / region.pixel_count().max(1) as f64
+            );
+        }
+        let res = (frame.to_pixel_container(), frame.to_geometry_buffer());
+        self.frame = Some(frame);
+        return res;
     }
 
-    fn save_image(&mut self, res_vec: &PixelContainer, process_label: String, num: usize) {
-        let image_buffer = ImageBuffer::<Rgb<u8>, Vec<u8>>::from_vec(
-            WINDOW_WIDTH,
-            WINDOW_HEIGHT,
-            res_vec.to_pixels(),
-        )
-        .unwrap();
-        println!("Saving {} image..", process_label);
-        image_buffer
-            .save(format!(
-                "0{}-{}SPP-{}.png",
-                num, SAMPLES_PER_PIXEL, process_label
-            ))
-            .unwrap();
+    fn finish_stage(
+        &mut self,
+        process_label: &str,
+        num: usize,
+        res_vec: &PixelContainer,
+        output: &OutputSettings,
+    ) -> ImageResult<()> {
+        if output.save_intermediate {
+            println!("Saving {} image..", process_label);
+            let path =
+                output.intermediate_path(process_label, num, self.settings.samples_per_pixel);
+            self.save_image(res_vec, &path)?;
+        }
         let t_end = SystemTime::now();
         println!(
             "Image {} time cost: {}, total cost: {}",
@@ -219,6 +467,21 @@ impl World {
             t_end.duration_since(self.start_time).unwrap().as_secs()
         );
         self.last_end_time = t_end;
+        return Ok(());
+    }
+
+    fn save_image(&self, res_vec: &PixelContainer, path: &Path) -> ImageResult<()> {
+        return save_pixels(res_vec, path);
+    }
+
+    fn save_rgb(&self, data: Vec<u8>, path: &Path) -> ImageResult<()> {
+        let image_buffer = ImageBuffer::<Rgb<u8>, Vec<u8>>::from_vec(
+            self.settings.width,
+            self.settings.height,
+            data,
+        )
+        .unwrap();
+        return image_buffer.save(path);
     }
 
     pub fn default_scene(&mut self) {
@@ -323,7 +586,166 @@ impl World {
             Point::new([300.0, 300.0, 800.0]),
             Vector3::new([0.0, 0.0, -1.0]),
             Vector3::new([0.0, 1.0, 0.0]),
+            &self.settings,
         ));
         self.objects = Arc::new(RwLock::new(objs));
+        self.bvh = None;
+        self.frame = None;
+    }
+
+    pub fn load_scene<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SceneError> {
+        let scene = SceneDesc::from_file(path)?;
+        return self.build_scene(&scene);
     }
+
+    // appends the triangles of an obj file, returns how many were added
+    pub fn load_obj<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        transform: &MeshTransform,
+        material: Option<Arc<dyn Material + Send + Sync>>,
+    ) -> Result<usize, SceneError> {
+        let triangles = load_obj(path, transform, material, self.object_count())?;
+        let num = triangles.len();
+        for triangle in triangles {
+            if triangle.is_light() {
+                self.add_light(triangle);
+            } else {
+                self.add(triangle);
+            }
+        }
+        return Ok(num);
+    }
+
+    pub fn build_scene(&mut self, scene: &SceneDesc) -> Result<(), SceneError> {
+        let mut materials: BTreeMap<&str, Arc<dyn Material + Send + Sync>> = BTreeMap::new();
+        for (name, desc) in scene.materials.iter() {
+            let material: Arc<dyn Material + Send + Sync> = match *desc {
+                MaterialDesc::Diffuse { color } => Arc::new(DiffuseMat::new(Color::new(color))),
+                MaterialDesc::Metal { color, fuzz } => {
+                    Arc::new(Metal::new(Color::new(color), fuzz))
+                }
+                MaterialDesc::Glass { color, eta } => Arc::new(Glass::new(Color::new(color), eta)),
+                MaterialDesc::DiffuseLight { color } => {
+                    Arc::new(DiffuseLight::new(Color::new(color)))
+                }
+            };
+            materials.insert(name.as_str(), material);
+        }
+
+        let mut objs: Vec<Arc<dyn Hittable + Send + Sync>> = Vec::new();
+        let mut lights: Vec<Arc<dyn HittableLight + Send + Sync>> = Vec::new();
+        for desc in scene.objects.iter() {
+            // references are checked while parsing, only meshes may omit them
+            let material = desc.material_name().map(|name| materials[name].clone());
+            match *desc {
+                ObjectDesc::Panel { points, normal, .. } => {
+                    let panel = Arc::new(Panel::new(
+                        [Point::new(points[0]), Point::new(points[1])],
+                        Vector3::new(normal).normalize(),
+                        material.unwrap(),
+                        objs.len(),
+                    ));
+                    if panel.is_light() {
+                        lights.push(panel.clone());
+                    }
+                    objs.push(panel);
+                }
+                ObjectDesc::Rectangle {
+                    points,
+                    angle_rotate_y,
+                    ..
+                } => {
+                    objs.push(Arc::new(Rectangle::new(
+                        [Point::new(points[0]), Point::new(points[1])],
+                        angle_rotate_y,
+                        material.unwrap(),
+                        objs.len(),
+                    )));
+                }
+                ObjectDesc::Sphere { center, radius, .. } => {
+                    objs.push(Arc::new(Sphere::new(
+                        Point::new(center),
+                        radius,
+                        material.unwrap(),
+                        objs.len(),
+                    )));
+                }
+                ObjectDesc::Triangle {
+                    vertices, normals, ..
+                } => {
+                    let triangle = Arc::new(Triangle::new(
+                        [
+                            Point::new(vertices[0]),
+                            Point::new(vertices[1]),
+                            Point::new(vertices[2]),
+                        ],
+                        normals.map(|normals| {
+                            [
+                                Vector3::new(normals[0]).normalize(),
+                                Vector3::new(normals[1]).normalize(),
+                                Vector3::new(normals[2]).normalize(),
+                            ]
+                        }),
+                        None,
+                        material.unwrap(),
+                        objs.len(),
+                    ));
+                    if triangle.is_light() {
+                        lights.push(triangle.clone());
+                    }
+                    objs.push(triangle);
+                }
+                ObjectDesc::Mesh {
+                    ref path,
+                    scale,
+                    angle_rotate_y,
+                    translate,
+                    ..
+                } => {
+                    let transform = MeshTransform {
+                        scale: scale.unwrap_or(1.0),
+                        angle_rotate_y,
+                        translate: Vector3::new(translate.unwrap_or([0.0; 3])),
+                    };
+                    for triangle in load_obj(path, &transform, material, objs.len())? {
+                        if triangle.is_light() {
+                            lights.push(triangle.clone());
+                        }
+                        objs.push(triangle);
+                    }
+                }
+            }
+        }
+
+        self.camera = Arc::new(Camera::new(
+            Point::new(scene.camera.position),
+            Vector3::new(scene.camera.lookat).normalize(),
+            Vector3::new(scene.camera.updir).normalize(),
+            &self.settings,
+        ));
+        self.objects = Arc::new(RwLock::new(objs));
+        self.bvh = None;
+        self.frame = None;
+        self.lights = Arc::new(RwLock::new(lights));
+        return Ok(());
+    }
+}
+
+// called by the render threads after every tile
+fn report_progress(done: usize, total: usize) {
+    let portion = done * 100 / total;
+    if portion > (done - 1) * 100 / total {
+        println!("{}% done.", portion);
+    }
+}
+
+fn save_pixels(res_vec: &PixelContainer, path: &Path) -> ImageResult<()> {
+    let image_buffer = ImageBuffer::<Rgb<u8>, Vec<u8>>::from_vec(
+        res_vec.width() as u32,
+        res_vec.height() as u32,
+        res_vec.to_pixels(),
+    )
+    .unwrap();
+    return image_buffer.save(path);
 }